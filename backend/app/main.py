@@ -1,6 +1,6 @@
 from fastapi import FastAPI, HTTPException
 from pydantic import BaseModel, Field
-from typing import List
+from typing import List, Literal, Tuple
 
 import stellar_utils
 from stellar_sdk.soroban.types import Address
@@ -15,13 +15,28 @@ competitions_db = {}
 # --- MODELOS DE DADOS (PYDANTIC) ---
 class CompetitionCreateRequest(BaseModel):
     entry_fee: int = Field(..., description="Taxa de entrada em stroops (1 XLM = 10_000_000 stroops)")
+    fixed_prizes: List[int] = Field([], description="Prêmios absolutos por posição, em stroops; a competição só abre inscrições depois de financiada. As porcentagens dividem o excedente")
+    remainder_policy: Literal["Admin", "Treasury", "ProRataWinners", "FirstPlace", "RollOver"] = Field(..., description="Destino da sobra do pool após os prêmios, sem valor padrão para que seja uma escolha explícita. Treasury paga o endereço `treasury`; RollOver exige ligar a próxima competição")
+    treasury: str = Field(..., description="Endereço (G... ou C...) da tesouraria da competição, que recebe a sobra com a política Treasury e quando as outras políticas não têm destino")
+    team_size: int = Field(0, description="Jogadores por time (ex.: 5 para 5v5); 0 = competição individual")
+    team_fee_mode: Literal["CaptainPaysAll", "PerMember"] = Field("CaptainPaysAll", description="Se o capitão paga a inscrição do time inteiro ou cada membro paga a sua ao aceitar o convite")
+    allowlist_root: str | None = Field(None, description="Raiz Merkle (hex) das contas ou usernames convidados; vazio = competição aberta")
+    allowlist_by_username: bool = Field(False, description="Se verdadeiro, as folhas da allowlist são usernames em vez de endereços")
+    fee_schedule: List[Tuple[int, int]] = Field([], description="Degraus (timestamp_limite, taxa) em ordem crescente, ex.: early bird; depois do último vale entry_fee")
     payout_rules: List[int] = Field(..., description="Lista de porcentagens x100. Ex: [5000, 3000, 2000] para 50%, 30%, 20%")
+    payout_brackets: List[Tuple[int, List[int]]] = Field([], description="Tabelas por tamanho do campo: (mínimo de participantes, porcentagens x100), em ordem crescente. Ex: [(2, [10000]), (6, [5000, 3000, 2000])]")
     deadline: int = Field(..., description="Timestamp Unix de quando a competição se encerra para novas entradas")
     min_participants: int = Field(..., description="Número mínimo de participantes para a competição ser válida")
+    max_participants: int = Field(0, description="Número máximo de participantes (0 = sem limite); acima disso as inscrições vão para a lista de espera")
+    results_deadline: int = Field(..., description="Timestamp Unix limite para o admin publicar os resultados; depois disso os participantes podem pedir reembolso")
+    check_in_end: int = Field(0, description="Timestamp Unix do fim da janela de check-in, que começa no deadline (0 = sem check-in)")
+    forfeit_no_shows: bool = Field(False, description="Se verdadeiro, a taxa de quem não fizer check-in vai para o prêmio em vez de ser devolvida")
+    forfeit_disqualified: bool = Field(False, description="Se verdadeiro, a taxa de jogadores desclassificados fica no prêmio em vez de ser devolvida")
 
 class JoinRequest(BaseModel):
     participant_public_key: str = Field(..., description="A chave pública Stellar (G...) do participante")
     username: str = Field(..., description="O nick do usuário no jogo (ex: CS:GO)")
+    proof: List[str] = Field([], description="Prova Merkle (hashes em hex) para competições privadas; vazia nas abertas")
 
 class DistributeRequest(BaseModel):
     leaderboard: List[str] = Field(..., description="Lista ordenada de usernames (nicks) dos vencedores")
@@ -40,7 +55,21 @@ async def create_competition(req: CompetitionCreateRequest):
             entry_fee=req.entry_fee,
             payout_rules=req.payout_rules,
             deadline=req.deadline,
-            min_participants=req.min_participants
+            min_participants=req.min_participants,
+            results_deadline=req.results_deadline,
+            max_participants=req.max_participants,
+            check_in_end=req.check_in_end,
+            forfeit_no_shows=req.forfeit_no_shows,
+            forfeit_disqualified=req.forfeit_disqualified,
+            fee_schedule=req.fee_schedule,
+            payout_brackets=req.payout_brackets,
+            fixed_prizes=req.fixed_prizes,
+            remainder_policy=req.remainder_policy,
+            treasury=req.treasury,
+            team_size=req.team_size,
+            team_fee_mode=req.team_fee_mode,
+            allowlist_root=req.allowlist_root,
+            allowlist_by_username=req.allowlist_by_username
         )
         
         # Salva no nosso "banco de dados"
@@ -75,7 +104,8 @@ async def get_join_transaction(contract_id: str, req: JoinRequest):
         unsigned_tx_xdr = stellar_utils.build_join_tx_xdr(
             participant_public_key=req.participant_public_key,
             username=req.username,
-            contract_id=contract_id
+            contract_id=contract_id,
+            proof=req.proof
         )
         return {"transaction_xdr": unsigned_tx_xdr}
     except Exception as e:
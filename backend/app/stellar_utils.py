@@ -7,10 +7,11 @@ from stellar_sdk import (
     Network,
     TransactionBuilder,
     SorobanServer,
-    Account
+    Account,
+    scval
 )
 from stellar_sdk.exceptions import NotFoundError
-from stellar_sdk.soroban.types import Address, Symbol, Vec, I128, U64, U32
+from stellar_sdk.soroban.types import Address, Bytes, Symbol, Vec, I128, U64, U32
 
 # --- Configuração ---
 HORIZON_SERVER_URL = "https://horizon-testnet.stellar.org"
@@ -29,23 +30,42 @@ ADMIN_KEYPAIR = Keypair.from_secret(ADMIN_SECRET)
 
 CONTRACT_WASM_PATH = os.getenv("CONTRACT_WASM_PATH")
 
+# Wrapper (SAC) do XLM na testnet, token usado nas competições criadas pela API
+XLM_TOKEN_CONTRACT = "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC"
+
 
 def deploy_contract(
     admin: Address,
     entry_fee: int,
     payout_rules: list[int],
     deadline: int,
-    min_participants: int
+    min_participants: int,
+    results_deadline: int,
+    remainder_policy: str,
+    treasury: str,
+    max_participants: int = 0,
+    check_in_end: int = 0,
+    forfeit_no_shows: bool = False,
+    forfeit_disqualified: bool = False,
+    fee_schedule: list[tuple[int, int]] = (),
+    payout_brackets: list[tuple[int, list[int]]] = (),
+    fixed_prizes: list[int] = (),
+    team_size: int = 0,
+    team_fee_mode: str = "CaptainPaysAll",
+    allowlist_root: str | None = None,
+    allowlist_by_username: bool = False
 ) -> str:
     """
-    Faz o deploy completo (Upload + Create + Initialize) de um novo contrato de competição.
+    Faz o deploy completo (Upload + Create) de um novo contrato de competição.
+    A configuração é passada ao construtor do contrato na mesma transação que o cria,
+    então ninguém consegue chamar a inicialização antes da plataforma.
     Esta versão faz o upload do WASM a cada execução.
     Retorna o ID (endereço) do novo contrato.
     """
     source_account = server.load_account(ADMIN_KEYPAIR.public_key)
     
     # --- Etapa 1: Upload do WASM ---
-    print("🚀 Etapa 1/3: Fazendo upload do WASM do contrato...")
+    print("🚀 Etapa 1/2: Fazendo upload do WASM do contrato...")
     tx_upload = TransactionBuilder(source_account, NETWORK_PASS, base_fee=100_000) \
         .append_upload_contract_wasm_op(wasm=CONTRACT_WASM_PATH) \
         .build()
@@ -68,11 +88,60 @@ def deploy_contract(
     print(f"✅ WASM Uploaded. Hash: {wasm_hash}")
 
 
-    # --- Etapa 2: Criar o Contrato a partir do Hash do WASM ---
-    print("\n🚀 Etapa 2/3: Criando o contrato a partir do hash...")
+    # --- Etapa 2: Criar o Contrato já inicializado pelo construtor ---
+    print("\n🚀 Etapa 2/2: Criando o contrato a partir do hash...")
     tx_create = TransactionBuilder(source_account, NETWORK_PASS, base_fee=100_000) \
-        .append_create_contract_op(wasm_hash=bytes.fromhex(wasm_hash)) \
-        .build()
+        .append_create_contract_op(
+            wasm_id=bytes.fromhex(wasm_hash),
+            address=ADMIN_KEYPAIR.public_key,
+            constructor_args=[
+                admin,
+                # CompetitionConfig: struct serializado como mapa pelos nomes dos campos
+                scval.to_struct({
+                    "token": scval.to_address(XLM_TOKEN_CONTRACT),
+                    "entry_fee": scval.to_int128(entry_fee),
+                    # Degraus (until, fee) em ordem crescente; depois do último vale `entry_fee`
+                    "fee_schedule": scval.to_vec([
+                        scval.to_struct({"until": scval.to_uint64(until), "fee": scval.to_int128(fee)})
+                        for until, fee in fee_schedule
+                    ]),
+                    # PayoutCurve::Table com os pontos-base informados
+                    "payout_rules": scval.to_enum("Table", scval.to_vec([scval.to_uint32(p) for p in payout_rules])),
+                    # Faixas (mínimo de participantes, tabela) em ordem crescente
+                    "payout_brackets": scval.to_vec([
+                        scval.to_struct({
+                            "min_participants": scval.to_uint32(min_participants_bracket),
+                            "payout_rules": scval.to_enum("Table", scval.to_vec([scval.to_uint32(p) for p in rules])),
+                        })
+                        for min_participants_bracket, rules in payout_brackets
+                    ]),
+                    # Prêmios absolutos por posição; exigem financiamento via `fund_prizes`
+                    "fixed_prizes": scval.to_vec([scval.to_int128(p) for p in fixed_prizes]),
+                    # Admin, Treasury, ProRataWinners, FirstPlace ou RollOver
+                    "remainder_policy": scval.to_enum(remainder_policy, None),
+                    # Obrigatória no deploy direto, sem fábrica
+                    "treasury": scval.to_address(treasury),
+                    # 0 = individual; em modo time o capitão usa `register_team`
+                    "team_size": scval.to_uint32(team_size),
+                    # CaptainPaysAll ou PerMember
+                    "team_fee_mode": scval.to_enum(team_fee_mode, None),
+                    # Raiz Merkle (hex) de uma competição privada; None = aberta
+                    "allowlist_root": scval.to_bytes(bytes.fromhex(allowlist_root)) if allowlist_root else scval.to_void(),
+                    "allowlist_leaf": scval.to_enum("Username" if allowlist_by_username else "Address", None),
+                    "deadline": scval.to_uint64(deadline),
+                    "min_participants": scval.to_uint32(min_participants),
+                    "max_participants": scval.to_uint32(max_participants),
+                    "results_deadline": scval.to_uint64(results_deadline),
+                    "check_in_end": scval.to_uint64(check_in_end),
+                    # Enum unitário: vetor com o nome da variante
+                    "no_show_policy": scval.to_enum("Forfeit" if forfeit_no_shows else "Refund", None),
+                    "disqualification_policy": scval.to_enum("Forfeit" if forfeit_disqualified else "Refund", None),
+                }),
+                # Deploy direto, sem fábrica e sem taxa da plataforma
+                scval.to_void(),
+                U32(0)
+            ]
+        ).build()
     
     prepared_create = soroban_server.prepare_transaction(tx_create)
     prepared_create.sign(ADMIN_KEYPAIR)
@@ -88,35 +157,15 @@ def deploy_contract(
         raise Exception(f"Transação de criação do contrato falhou: {tx_status.result_xdr}")
 
     contract_id = tx_status.result_xdr.v3.results[0].tr.invoke_host_fn_result.success.address.contract_id.hex()
-    print(f"✅ Contrato criado. ID: {contract_id}")
-    
-
-    # --- Etapa 3: Inicialização do Contrato ---
-    print("\n🚀 Etapa 3/3: Inicializando o contrato...")
-    tx_init = TransactionBuilder(source_account, NETWORK_PASS, base_fee=100_000) \
-        .append_invoke_contract_function_op(
-            contract_id=contract_id,
-            function_name="initialize",
-            parameters=[
-                admin,
-                I128(entry_fee),
-                Vec([U32(p) for p in payout_rules]),
-                U64(deadline),
-                U32(min_participants)
-            ]
-        ).build()
-
-    prepared_init = soroban_server.prepare_transaction(tx_init)
-    prepared_init.sign(ADMIN_KEYPAIR)
-    sent_init = soroban_server.send_transaction(prepared_init)
-    print(f"✅ Transação de inicialização enviada. Hash: {sent_init.hash}")
+    print(f"✅ Contrato criado e inicializado. ID: {contract_id}")
 
     return contract_id
 
 
-def build_join_tx_xdr(participant_public_key: str, username: str, contract_id: str) -> str:
+def build_join_tx_xdr(participant_public_key: str, username: str, contract_id: str, proof: list[str] = ()) -> str:
     """
     Constrói a transação para o usuário entrar na competição, com o footprint correto.
+    Em competições privadas, `proof` traz os hashes (hex) da prova de inclusão na allowlist.
     Retorna o XDR da transação para o frontend assinar.
     """
     # Validação: Verifica se a conta do participante existe na rede
@@ -132,7 +181,8 @@ def build_join_tx_xdr(participant_public_key: str, username: str, contract_id: s
             function_name="join",
             parameters=[
                 Address.from_string(participant_public_key),
-                Symbol(username)
+                Symbol(username),
+                Vec([Bytes(bytes.fromhex(node)) for node in proof])
             ],
             # A autorização (e o pagamento da taxa) vem do participante
             source=participant_public_key 
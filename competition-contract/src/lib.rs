@@ -1,9 +1,26 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, token, Address, Env, Map, Symbol, Vec,
-    String as SdkString,
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractevent, contractimpl, contracttype, symbol_short, token, xdr::ToXdr, Address,
+    Bytes, BytesN, Env, Error, vec, IntoVal, Map, Symbol, Vec, String as SdkString,
 };
 
+// Versão do layout de armazenamento. Incrementar sempre que `migrate` precisar converter dados.
+// v1: layout original, com o mapa de participantes no armazenamento de instância.
+// v2: participantes no armazenamento persistente; papéis de oráculo/tesouraria e prazo de resultados.
+// v3: token da competição configurável (`DataKey::Token`).
+// v4: total em custódia (`DataKey::Escrow`) e depósito registrado por inscrição.
+const SCHEMA_VERSION: u32 = 4;
+
+// Wrapper do XLM na testnet, usado por todas as competições anteriores ao token configurável.
+const LEGACY_XLM_TOKEN: &str = "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC";
+
+// Limite de posições premiadas geradas por uma curva de prêmios.
+const MAX_PAYOUT_PLACES: u32 = 100;
+
+// Prazo padrão para publicar resultados em contratos v1, que não tinham `ResultsDeadline`.
+const DEFAULT_RESULTS_WINDOW: u64 = 7 * 24 * 60 * 60;
+
 // Enumeração que define as chaves de armazenamento para este contrato
 #[contracttype]
 #[derive(Clone)]
@@ -15,73 +32,1767 @@ pub enum DataKey {
     IsActive,
     Deadline,
     MinParticipants,
+    ResultsDeadline,
+    PendingAdmin,
+    Oracle,
+    Treasury,
+    SchemaVersion,
+    Factory,
+    Paused,
+    Token,
+    PlatformFeeBps,
+    MaxParticipants,
+    Waitlist,
+    CheckInEnd,
+    NoShowPolicy,
+    CheckedIn,
+    CheckInFinalized,
+    // Total retido antes do registro por jogador (`NoShows`, `Disqualification`), lido por `migrate`
+    ForfeitedPool,
+    Moderator,
+    DisqualificationPolicy,
+    Disqualified,
+    FeeSchedule,
+    FeesPaid,
+    Escrow,
+    PayoutBrackets,
+    PayoutCurve,
+    FixedPrizes,
+    Sponsors,
+    RemainderPolicy,
+    NextCompetition,
+    SettlementReceipt,
+    TeamSize,
+    TeamFeeMode,
+    Teams,
+    AllowlistRoot,
+    AllowlistLeaf,
+    RolledOver,
+    NoShows,
+}
+
+// O que cada folha da allowlist identifica: o endereço de quem entra ou o username escolhido.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AllowlistLeaf {
+    Address,
+    Username,
+}
+
+// Quem paga a inscrição de um time: o capitão paga pelo time inteiro, ou cada membro paga a
+// própria taxa ao aceitar o convite.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TeamFeeMode {
+    CaptainPaysAll,
+    PerMember,
+}
+
+// Time registrado por um capitão. Os vetores são paralelos a `members`, com o capitão na
+// posição 0. O time só entra em `participants` quando todos aceitam o convite.
+#[contracttype]
+#[derive(Clone)]
+pub struct Team {
+    pub captain: Address,
+    pub members: Vec<Address>,
+    // Divisão do prêmio em pontos-base por membro; vazio = divisão igual
+    pub shares: Vec<u32>,
+    pub accepted: Vec<bool>,
+    pub contributions: Vec<i128>,
+}
+
+// Destino do que sobra do pool depois dos prêmios (posições sem vencedor e arredondamento).
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RemainderPolicy {
+    Admin,
+    Treasury,
+    // Dividido entre os vencedores na proporção do que cada um recebeu
+    ProRataWinners,
+    FirstPlace,
+    // Vai para o pool da competição ligada por `set_next_competition`
+    RollOver,
+}
+
+// Resumo da liquidação, guardado por `distribute_prizes`.
+#[contracttype]
+#[derive(Clone)]
+pub struct SettlementReceipt {
+    pub total_prize_pool: i128,
+    pub platform_fee: i128,
+    pub paid_out: i128,
+    // Prêmios fixos sem vencedor, devolvidos aos patrocinadores
+    pub sponsor_refund: i128,
+    pub remainder: i128,
+    pub remainder_policy: RemainderPolicy,
+    // Política de fato aplicada: cai para `Treasury` quando a configurada não tem destino
+    pub applied_policy: RemainderPolicy,
+    // `None` quando a sobra foi dividida entre os vencedores
+    pub remainder_recipient: Option<Address>,
+    pub settled_at: u64,
+}
+
+// O que acontece com a taxa de quem não fez check-in.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NoShowPolicy {
+    Refund,
+    Forfeit,
+}
+
+// O que acontece com a taxa de um jogador desclassificado.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DisqualificationPolicy {
+    Refund,
+    Forfeit,
+}
+
+// Ausente cuja taxa ficou retida no fim do check-in, consultável por `get_no_show`.
+#[contracttype]
+#[derive(Clone)]
+pub struct NoShow {
+    pub address: Address,
+    // Volta ao jogador se a competição não for liquidada
+    pub forfeited_fee: i128,
+}
+
+// Registro de uma desclassificação, consultável por `get_disqualification`.
+#[contracttype]
+#[derive(Clone)]
+pub struct Disqualification {
+    pub address: Address,
+    // Todos os jogadores do time desclassificado, capitão incluído; vazio fora do modo time
+    pub members: Vec<Address>,
+    pub reason: SdkString,
+    pub refunded: bool,
+    // Taxa retida pela política `Forfeit`; volta ao jogador se a competição não for liquidada
+    pub forfeited_fee: i128,
+}
+
+// Degrau da tabela de taxas: vale para inscrições feitas até `until`, inclusive.
+#[contracttype]
+#[derive(Clone)]
+pub struct FeeStep {
+    pub until: u64,
+    pub fee: i128,
+}
+
+// Forma de uma tabela de prêmios. Fora `Table`, que traz os pontos-base prontos, as curvas são
+// expandidas pelo contrato (`expand_payout_curve`) e sempre somam exatamente 10000.
+#[contracttype]
+#[derive(Clone)]
+pub enum PayoutCurve {
+    Table(Vec<u32>),
+    WinnerTakesAll,
+    // Divide o pool igualmente entre os N primeiros
+    TopNEqual(u32),
+    // Pesos N, N-1, ..., 1
+    Linear(u32),
+    // N posições, cada uma recebendo `ratio` pontos-base da anterior (ex.: 5000 = metade)
+    Geometric(u32, u32),
+}
+
+// Tabela de prêmios usada quando o campo tem pelo menos `min_participants` jogadores.
+#[contracttype]
+#[derive(Clone)]
+pub struct PayoutBracket {
+    pub min_participants: u32,
+    pub payout_rules: PayoutCurve,
+}
+
+// Parâmetros da competição passados ao construtor. Espelha o tipo de mesmo nome na fábrica.
+#[contracttype]
+#[derive(Clone)]
+pub struct CompetitionConfig {
+    pub token: Address,
+    // Taxa cobrada depois do último degrau de `fee_schedule` (ou sempre, se ela estiver vazia)
+    pub entry_fee: i128,
+    // Degraus em ordem crescente de `until`, ex.: early bird mais barato
+    pub fee_schedule: Vec<FeeStep>,
+    // Tabela padrão, usada quando nenhuma faixa de `payout_brackets` se aplica
+    pub payout_rules: PayoutCurve,
+    // Faixas em ordem crescente de `min_participants`; vale a maior faixa que o campo atinge
+    pub payout_brackets: Vec<PayoutBracket>,
+    // Prêmios em valor absoluto por posição, pagos antes da tabela percentual, que passa a
+    // dividir só o excedente. Precisam ser financiados (`fund_prizes`) antes das inscrições.
+    pub fixed_prizes: Vec<i128>,
+    pub remainder_policy: RemainderPolicy,
+    // Tesouraria de uma competição sem fábrica, obrigatória nesse caso. Competições implantadas
+    // por uma fábrica deixam o campo vazio e pagam a tesouraria da plataforma.
+    pub treasury: Option<Address>,
+    // Jogadores por time; 0 = competição individual. Em modo time o leaderboard traz nomes de time.
+    pub team_size: u32,
+    pub team_fee_mode: TeamFeeMode,
+    // Raiz Merkle de uma competição privada; `None` = aberta a qualquer um
+    pub allowlist_root: Option<BytesN<32>>,
+    pub allowlist_leaf: AllowlistLeaf,
+    pub deadline: u64,
+    pub min_participants: u32,
+    // 0 = sem limite; acima do limite as inscrições vão para a lista de espera
+    pub max_participants: u32,
+    pub results_deadline: u64,
+    // Fim da janela de check-in, que vai de `deadline` até aqui; 0 = sem check-in
+    pub check_in_end: u64,
+    pub no_show_policy: NoShowPolicy,
+    pub disqualification_policy: DisqualificationPolicy,
+}
+
+// Inscrição na lista de espera, com a taxa de entrada já depositada.
+#[contracttype]
+#[derive(Clone)]
+pub struct WaitlistEntry {
+    pub username: Symbol,
+    pub address: Address,
+}
+
+// Eventos emitidos na troca de admin e na rotação de papéis
+#[contractevent]
+#[derive(Clone)]
+pub struct AdminProposed {
+    #[topic]
+    pub current: Address,
+    pub proposed: Address,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct AdminTransferred {
+    #[topic]
+    pub previous: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct RoleRotated {
+    #[topic]
+    pub role: Symbol,
+    pub previous: Address,
+    pub new_holder: Address,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct Upgraded {
+    pub new_wasm_hash: BytesN<32>,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct Migrated {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+// Transições reportadas à fábrica que implantou a competição (`report_lifecycle`).
+// Espelha o tipo de mesmo nome na fábrica.
+#[contracttype]
+#[derive(Clone)]
+pub enum LifecycleEvent {
+    Joined(u32),
+    Settled(i128),
+    Cancelled,
+    Refunded,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct Waitlisted {
+    #[topic]
+    pub username: Symbol,
+    pub position: u32,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct WaitlistPromoted {
+    #[topic]
+    pub username: Symbol,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct CheckedIn {
+    #[topic]
+    pub username: Symbol,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct NoShowRemoved {
+    #[topic]
+    pub username: Symbol,
+    pub refunded: bool,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct PlayerDisqualified {
+    #[topic]
+    pub username: Symbol,
+    pub reason: SdkString,
+    pub refunded: bool,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct PrizesFunded {
+    #[topic]
+    pub funder: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct RollOverReceived {
+    #[topic]
+    pub from: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct NextCompetitionLinked {
+    pub next_competition: Address,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct PrizesDistributed {
+    pub total_prize_pool: i128,
+    pub paid_out: i128,
+    pub remainder: i128,
+    pub remainder_policy: RemainderPolicy,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct TeamRegistered {
+    #[topic]
+    pub team_name: Symbol,
+    pub captain: Address,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct InvitationAccepted {
+    #[topic]
+    pub team_name: Symbol,
+    pub member: Address,
+    pub team_complete: bool,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct AllowlistRootRotated {
+    pub previous: Option<BytesN<32>>,
+    pub new_root: Option<BytesN<32>>,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct PauseChanged {
+    pub paused: bool,
+}
+
+fn token_client(env: &Env) -> token::Client<'_> {
+    let token_address: Address = env.storage().instance().get(&DataKey::Token).unwrap();
+    token::Client::new(env, &token_address)
+}
+
+fn report_to_factory(env: &Env, event: LifecycleEvent) {
+    if let Some(factory) = env.storage().instance().get::<_, Address>(&DataKey::Factory) {
+        env.invoke_contract::<()>(
+            &factory,
+            &Symbol::new(env, "report_lifecycle"),
+            (env.current_contract_address(), event).into_val(env),
+        );
+    }
+}
+
+// Total em custódia: depósitos de participantes e da lista de espera, mais taxas retidas.
+fn escrow(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::Escrow).unwrap_or(0)
+}
+
+fn debit_escrow(env: &Env, amount: i128) {
+    let escrow = escrow(env);
+    if amount > escrow {
+        panic!("Amount exceeds escrowed funds");
+    }
+    env.storage().instance().set(&DataKey::Escrow, &(escrow - amount));
+}
+
+// Toda saída de fundos do contrato passa por aqui para manter o total em custódia em dia.
+fn release(env: &Env, to: &Address, amount: i128) {
+    debit_escrow(env, amount);
+    token_client(env).transfer(&env.current_contract_address(), to, &amount);
+}
+
+// Tira `amount` da custódia e pré-autoriza a transferência dele para `to`, feita em seguida por
+// outro contrato que este chama (a fábrica em `deposit_fee`, a próxima competição em `fund_prizes`).
+fn authorize_transfer(env: &Env, to: &Address, amount: i128) {
+    debit_escrow(env, amount);
+
+    let token_address: Address = env.storage().instance().get(&DataKey::Token).unwrap();
+    env.authorize_as_current_contract(vec![
+        env,
+        InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: token_address,
+                fn_name: Symbol::new(env, "transfer"),
+                args: (env.current_contract_address(), to.clone(), amount).into_val(env),
+            },
+            sub_invocations: vec![env],
+        }),
+    ]);
+}
+
+// A fábrica puxa o valor em `deposit_fee` e credita na tesouraria da plataforma só o que recebeu.
+fn deposit_with_factory(env: &Env, factory: &Address, amount: i128) {
+    authorize_transfer(env, factory, amount);
+    env.invoke_contract::<()>(
+        factory,
+        &Symbol::new(env, "deposit_fee"),
+        (env.current_contract_address(), amount).into_val(env),
+    );
+}
+
+// Paga a tesouraria e devolve o endereço dela. Em competições de uma fábrica a tesouraria é a
+// própria fábrica.
+fn pay_treasury(env: &Env, amount: i128) -> Address {
+    let treasury: Address = env.storage().instance().get(&DataKey::Treasury).unwrap();
+    if env.storage().instance().get::<_, Address>(&DataKey::Factory) == Some(treasury.clone()) {
+        deposit_with_factory(env, &treasury, amount);
+    } else {
+        release(env, &treasury, amount);
+    }
+    treasury
+}
+
+// Envia a sobra para o pool da próxima competição (`receive_roll_over`).
+fn roll_over(env: &Env, next_competition: &Address, amount: i128) {
+    authorize_transfer(env, next_competition, amount);
+    env.invoke_contract::<()>(
+        next_competition,
+        &Symbol::new(env, "receive_roll_over"),
+        (env.current_contract_address(), amount).into_val(env),
+    );
+}
+
+// A competição ligada só recebe a sobra enquanto estiver ativa; uma que não responda a
+// `is_active` também é ignorada, para não travar a liquidação desta.
+fn accepts_roll_over(env: &Env, next_competition: &Address) -> bool {
+    let is_active = env.try_invoke_contract::<bool, Error>(
+        next_competition,
+        &Symbol::new(env, "is_active"),
+        Vec::new(env),
+    );
+    is_active == Ok(Ok(true))
+}
+
+fn remainder_policy(env: &Env) -> RemainderPolicy {
+    // Antes da política configurável, a sobra ia para a tesouraria
+    env.storage()
+        .instance()
+        .get(&DataKey::RemainderPolicy)
+        .unwrap_or(RemainderPolicy::Treasury)
+}
+
+fn load_waitlist(env: &Env) -> Vec<WaitlistEntry> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Waitlist)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+// A vaga liberada por uma saída ou desclassificação vai para o primeiro da lista de espera
+// enquanto as inscrições estão abertas.
+fn promote_from_waitlist(env: &Env, participants: &mut Map<Symbol, Address>) {
+    let deadline: u64 = env.storage().instance().get(&DataKey::Deadline).unwrap();
+    if env.ledger().timestamp() > deadline {
+        return;
+    }
+
+    let mut waitlist = load_waitlist(env);
+    if let Some(promoted) = waitlist.pop_front() {
+        participants.set(promoted.username.clone(), promoted.address);
+        env.storage().persistent().set(&DataKey::Waitlist, &waitlist);
+
+        WaitlistPromoted { username: promoted.username }.publish(env);
+    }
+}
+
+// Devolve a taxa de todos que ainda estão na lista de espera e a esvazia.
+fn refund_waitlist(env: &Env) {
+    let waitlist = load_waitlist(env);
+    if waitlist.is_empty() {
+        return;
+    }
+
+    for entry in waitlist.iter() {
+        let fee_paid = remove_fee_paid(env, &entry.username);
+        release(env, &entry.address, fee_paid);
+    }
+    env.storage().persistent().remove(&DataKey::Waitlist);
+}
+
+fn team_size(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::TeamSize).unwrap_or(0)
+}
+
+fn load_teams(env: &Env) -> Map<Symbol, Team> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Teams)
+        .unwrap_or_else(|| Map::new(env))
+}
+
+fn is_team_complete(team: &Team) -> bool {
+    team.accepted.iter().all(|accepted| accepted)
+}
+
+// Devolve o depósito de uma inscrição. Em times, cada membro recebe o que pagou e o time sai
+// do registro.
+fn refund_entry(env: &Env, username: &Symbol, address: &Address, amount: i128) {
+    let mut teams = load_teams(env);
+    if let Some(team) = teams.get(username.clone()) {
+        for (member, contribution) in team.members.iter().zip(team.contributions.iter()) {
+            if contribution > 0 {
+                release(env, &member, contribution);
+            }
+        }
+        teams.remove(username.clone());
+        env.storage().persistent().set(&DataKey::Teams, &teams);
+        return;
+    }
+    release(env, address, amount);
+}
+
+// Paga um prêmio. Em times, o valor é dividido pelas cotas definidas pelo capitão (ou
+// igualmente) e a poeira do arredondamento fica com o capitão.
+fn pay_entry(env: &Env, username: &Symbol, address: &Address, amount: i128) {
+    let Some(team) = load_teams(env).get(username.clone()) else {
+        release(env, address, amount);
+        return;
+    };
+
+    let member_count = team.members.len() as i128;
+    let mut payouts: Vec<i128> = Vec::new(env);
+    let mut distributed: i128 = 0;
+    for i in 0..team.members.len() {
+        let payout = if team.shares.is_empty() {
+            amount / member_count
+        } else {
+            amount * team.shares.get(i).unwrap() as i128 / 10000
+        };
+        payouts.push_back(payout);
+        distributed += payout;
+    }
+    payouts.set(0, payouts.get(0).unwrap() + amount - distributed);
+
+    for (member, payout) in team.members.iter().zip(payouts.iter()) {
+        if payout > 0 {
+            release(env, &member, payout);
+        }
+    }
+}
+
+// Times que não fecharam o elenco não disputam; os depósitos voltam a quem pagou.
+fn refund_pending_teams(env: &Env) {
+    for (team_name, team) in load_teams(env).iter() {
+        if !is_team_complete(&team) {
+            let fee_paid = remove_fee_paid(env, &team_name);
+            refund_entry(env, &team_name, &team.captain, fee_paid);
+        }
+    }
+}
+
+fn allowlist_leaf_kind(env: &Env) -> AllowlistLeaf {
+    env.storage()
+        .instance()
+        .get(&DataKey::AllowlistLeaf)
+        .unwrap_or(AllowlistLeaf::Address)
+}
+
+// Folha da allowlist: sha256 do XDR do endereço ou do username, conforme a competição.
+fn allowlist_leaf(env: &Env, participant: &Address, username: &Symbol) -> BytesN<32> {
+    let data = match allowlist_leaf_kind(env) {
+        AllowlistLeaf::Address => participant.clone().to_xdr(env),
+        AllowlistLeaf::Username => username.clone().to_xdr(env),
+    };
+    env.crypto().sha256(&data).into()
+}
+
+// Sobe da folha até a raiz. Cada par é ordenado antes do hash, então a prova não precisa
+// indicar de que lado fica cada irmão.
+fn merkle_root_from_proof(env: &Env, leaf: BytesN<32>, proof: &Vec<BytesN<32>>) -> BytesN<32> {
+    let mut node = leaf;
+    for sibling in proof.iter() {
+        let mut data = Bytes::new(env);
+        if node.to_array() <= sibling.to_array() {
+            data.append(&node.into());
+            data.append(&sibling.into());
+        } else {
+            data.append(&sibling.into());
+            data.append(&node.into());
+        }
+        node = env.crypto().sha256(&data).into();
+    }
+    node
+}
+
+// Em competições privadas, exige uma prova de inclusão válida para a raiz atual.
+fn require_allowlisted(env: &Env, participant: &Address, username: &Symbol, proof: &Vec<BytesN<32>>) {
+    let Some(root) = env.storage().instance().get::<_, BytesN<32>>(&DataKey::AllowlistRoot) else {
+        return;
+    };
+    let leaf = allowlist_leaf(env, participant, username);
+    if merkle_root_from_proof(env, leaf, proof) != root {
+        panic!("Not on the allowlist");
+    }
+}
+
+// Transfere o valor para o contrato e devolve quanto de fato chegou, o que é registrado
+// como depósito (tokens com taxa de transferência entregam menos que o nominal).
+fn collect_deposit(env: &Env, from: &Address, amount: i128) -> i128 {
+    let token_client = token_client(env);
+    let contract_address = env.current_contract_address();
+    let balance_before = token_client.balance(&contract_address);
+    token_client.transfer(from, &contract_address, &amount);
+    let deposited = token_client.balance(&contract_address) - balance_before;
+    if deposited < 0 || (amount > 0 && deposited == 0) {
+        panic!("Deposit was not received");
+    }
+    deposited
+}
+
+fn require_registration_open(env: &Env) {
+    let is_active: bool = env.storage().instance().get(&DataKey::IsActive).unwrap();
+    if !is_active {
+        panic!("Competition is closed");
+    }
+    let deadline: u64 = env.storage().instance().get(&DataKey::Deadline).unwrap();
+    if env.ledger().timestamp() > deadline {
+        panic!("Registration is closed");
+    }
+
+    // Com prêmios fixos, as inscrições só abrem depois que o pool estiver financiado
+    if sponsored_total(env) < fixed_prizes_total(env) {
+        panic!("Fixed prizes are not funded yet");
+    }
+}
+
+// Converte uma curva em pontos-base por posição. Os pesos são normalizados com arredondamento
+// para baixo e a sobra (menor que o número de posições) vai, um ponto por vez, para as primeiras
+// posições, então a tabela soma exatamente 10000 e continua não crescente.
+fn expand_payout_curve(env: &Env, curve: &PayoutCurve) -> Vec<u32> {
+    let (places, ratio_bps): (u32, u32) = match curve {
+        PayoutCurve::Table(payout_rules) => {
+            let mut total: u32 = 0;
+            for bps in payout_rules.iter() {
+                total = total.saturating_add(bps);
+            }
+            if total > 10000 {
+                panic!("Payout rules cannot exceed 10000 basis points");
+            }
+            return payout_rules.clone();
+        }
+        PayoutCurve::WinnerTakesAll => (1, 10000),
+        PayoutCurve::TopNEqual(places) => (*places, 10000),
+        PayoutCurve::Linear(places) => (*places, 0),
+        PayoutCurve::Geometric(places, ratio_bps) => {
+            if *ratio_bps == 0 || *ratio_bps > 10000 {
+                panic!("Geometric ratio must be between 1 and 10000 basis points");
+            }
+            (*places, *ratio_bps)
+        }
+    };
+    if places == 0 || places > MAX_PAYOUT_PLACES {
+        panic!("Payout curve must pay between 1 and 100 places");
+    }
+
+    let mut weights: Vec<i128> = Vec::new(env);
+    let mut weight: i128 = 1_000_000_000_000;
+    for place in 0..places {
+        match curve {
+            PayoutCurve::Linear(_) => weights.push_back((places - place) as i128),
+            _ => {
+                weights.push_back(weight);
+                weight = weight * ratio_bps as i128 / 10000;
+            }
+        }
+    }
+
+    let mut total_weight: i128 = 0;
+    for weight in weights.iter() {
+        total_weight += weight;
+    }
+
+    let mut payout_rules: Vec<u32> = Vec::new(env);
+    let mut assigned: u32 = 0;
+    for weight in weights.iter() {
+        let bps = (weight * 10000 / total_weight) as u32;
+        payout_rules.push_back(bps);
+        assigned += bps;
+    }
+    for place in 0..(10000 - assigned) {
+        let bps = payout_rules.get(place).unwrap();
+        payout_rules.set(place, bps + 1);
+    }
+    payout_rules
+}
+
+fn fixed_prizes(env: &Env) -> Vec<i128> {
+    env.storage()
+        .instance()
+        .get(&DataKey::FixedPrizes)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn fixed_prizes_total(env: &Env) -> i128 {
+    let mut total: i128 = 0;
+    for amount in fixed_prizes(env).iter() {
+        total += amount;
+    }
+    total
+}
+
+fn load_sponsors(env: &Env) -> Map<Address, i128> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Sponsors)
+        .unwrap_or_else(|| Map::new(env))
+}
+
+fn sponsored_total(env: &Env) -> i128 {
+    let mut total: i128 = 0;
+    for amount in load_sponsors(env).values().iter() {
+        total += amount;
+    }
+    total
+}
+
+// Devolve aos patrocinadores, na proporção do que cada um financiou, os prêmios fixos que
+// ficaram sem vencedor. A poeira da divisão fica com o maior patrocinador.
+fn refund_unpaid_fixed_prizes(env: &Env, amount: i128) {
+    if amount <= 0 {
+        return;
+    }
+    let sponsors = load_sponsors(env);
+    let total = sponsored_total(env);
+
+    let mut largest: Option<(Address, i128)> = None;
+    let mut distributed: i128 = 0;
+    for (funder, funded) in sponsors.iter() {
+        let share = amount * funded / total;
+        if share > 0 {
+            release(env, &funder, share);
+            distributed += share;
+        }
+        if largest.as_ref().is_none_or(|(_, largest_funded)| funded > *largest_funded) {
+            largest = Some((funder, funded));
+        }
+    }
+    if let Some((funder, _)) = largest {
+        if amount > distributed {
+            release(env, &funder, amount - distributed);
+        }
+    }
+}
+
+// Devolve o financiamento dos patrocinadores quando a competição não chega à liquidação.
+fn refund_sponsors(env: &Env) {
+    for (funder, amount) in load_sponsors(env).iter() {
+        release(env, &funder, amount);
+    }
+    env.storage().persistent().remove(&DataKey::Sponsors);
+}
+
+fn rolled_over(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::RolledOver).unwrap_or(0)
+}
+
+// A competição de origem já foi liquidada e não tem como receber nada de volta, então a sobra
+// recebida vai para a tesouraria quando esta competição não chega à liquidação.
+fn release_rolled_over(env: &Env) {
+    let amount = rolled_over(env);
+    if amount > 0 {
+        pay_treasury(env, amount);
+    }
+    env.storage().instance().remove(&DataKey::RolledOver);
+}
+
+// Curva padrão. Contratos anteriores às curvas guardam a tabela bruta em `PayoutRules`.
+fn default_payout_curve(env: &Env) -> PayoutCurve {
+    env.storage()
+        .instance()
+        .get(&DataKey::PayoutCurve)
+        .unwrap_or_else(|| {
+            PayoutCurve::Table(env.storage().instance().get(&DataKey::PayoutRules).unwrap())
+        })
+}
+
+// Tabela de prêmios para um campo de `field_size` jogadores.
+fn payout_rules_for(env: &Env, field_size: u32) -> Vec<u32> {
+    let payout_brackets: Vec<PayoutBracket> = env
+        .storage()
+        .instance()
+        .get(&DataKey::PayoutBrackets)
+        .unwrap_or_else(|| Vec::new(env));
+
+    let mut curve = default_payout_curve(env);
+    for bracket in payout_brackets.iter() {
+        if field_size < bracket.min_participants {
+            break;
+        }
+        curve = bracket.payout_rules;
+    }
+    expand_payout_curve(env, &curve)
+}
+
+// Taxa vigente: o primeiro degrau da tabela cujo prazo ainda não passou, ou a taxa base.
+fn current_entry_fee(env: &Env) -> i128 {
+    let now = env.ledger().timestamp();
+    let fee_schedule: Vec<FeeStep> =
+        env.storage().instance().get(&DataKey::FeeSchedule).unwrap_or_else(|| Vec::new(env));
+    for step in fee_schedule.iter() {
+        if now <= step.until {
+            return step.fee;
+        }
+    }
+    env.storage().instance().get(&DataKey::EntryFee).unwrap()
+}
+
+// Valor efetivamente recebido por inscrição, de participantes e da lista de espera.
+fn load_fees_paid(env: &Env) -> Map<Symbol, i128> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::FeesPaid)
+        .unwrap_or_else(|| Map::new(env))
+}
+
+// Inscrições anteriores à tabela de taxas não têm registro e pagaram a taxa base.
+fn fee_paid(env: &Env, fees_paid: &Map<Symbol, i128>, username: &Symbol) -> i128 {
+    fees_paid
+        .get(username.clone())
+        .unwrap_or_else(|| env.storage().instance().get(&DataKey::EntryFee).unwrap())
+}
+
+fn remove_fee_paid(env: &Env, username: &Symbol) -> i128 {
+    let mut fees_paid = load_fees_paid(env);
+    let amount = fee_paid(env, &fees_paid, username);
+    fees_paid.remove(username.clone());
+    env.storage().persistent().set(&DataKey::FeesPaid, &fees_paid);
+    amount
+}
+
+fn load_disqualified(env: &Env) -> Map<Symbol, Disqualification> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Disqualified)
+        .unwrap_or_else(|| Map::new(env))
 }
 
-#[contract]
-pub struct CompetitionContract;
+// Um jogador desclassificado, sozinho ou como membro de um time, não pode voltar a se inscrever.
+fn is_disqualified(env: &Env, player: &Address) -> bool {
+    load_disqualified(env)
+        .values()
+        .iter()
+        .any(|dq| dq.address == *player || dq.members.contains(player))
+}
+
+// Contratos anteriores ao papel de moderador usam o admin.
+fn moderator(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::Moderator)
+        .unwrap_or_else(|| env.storage().instance().get(&DataKey::Admin).unwrap())
+}
+
+fn load_no_shows(env: &Env) -> Map<Symbol, NoShow> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::NoShows)
+        .unwrap_or_else(|| Map::new(env))
+}
+
+// Sem liquidação não há pool para as taxas retidas. A de cada ausente ou desclassificado volta
+// para ele (em times, para quem pagou), e nunca para um endereço escolhido pelo admin.
+fn return_forfeited_fees(env: &Env) {
+    for (username, no_show) in load_no_shows(env).iter() {
+        refund_entry(env, &username, &no_show.address, no_show.forfeited_fee);
+    }
+    env.storage().persistent().remove(&DataKey::NoShows);
+
+    let mut disqualified = load_disqualified(env);
+    for (username, mut record) in disqualified.clone().iter() {
+        if record.forfeited_fee > 0 {
+            refund_entry(env, &username, &record.address, record.forfeited_fee);
+            record.forfeited_fee = 0;
+            record.refunded = true;
+            disqualified.set(username, record);
+        }
+    }
+    env.storage().persistent().set(&DataKey::Disqualified, &disqualified);
+}
+
+fn load_checked_in(env: &Env) -> Vec<Symbol> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::CheckedIn)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn check_in_end(env: &Env) -> u64 {
+    env.storage().instance().get(&DataKey::CheckInEnd).unwrap_or(0)
+}
+
+// Número de jogadores considerado para `min_participants`: com check-in, só quem confirmou presença.
+fn field_size(env: &Env) -> u32 {
+    if check_in_end(env) != 0 {
+        return load_checked_in(env).len();
+    }
+    let participants: Map<Symbol, Address> =
+        env.storage().persistent().get(&DataKey::Participants).unwrap();
+    participants.len()
+}
+
+// Remove do campo quem não fez check-in, devolvendo ou retendo a taxa conforme a política.
+// Só roda uma vez, depois do fim da janela.
+fn finalize_check_in(env: &Env) {
+    let check_in_end = check_in_end(env);
+    if check_in_end == 0 {
+        return;
+    }
+    let finalized: bool = env.storage().instance().get(&DataKey::CheckInFinalized).unwrap_or(false);
+    if finalized {
+        return;
+    }
+    if env.ledger().timestamp() <= check_in_end {
+        panic!("Check-in window is still open");
+    }
+
+    let min_participants: u32 = env.storage().instance().get(&DataKey::MinParticipants).unwrap();
+    let checked_in = load_checked_in(env);
+    if checked_in.len() < min_participants {
+        panic!("Not enough checked-in players");
+    }
+
+    let mut participants: Map<Symbol, Address> =
+        env.storage().persistent().get(&DataKey::Participants).unwrap();
+    let policy: NoShowPolicy = env.storage().instance().get(&DataKey::NoShowPolicy).unwrap();
+    let mut no_shows = load_no_shows(env);
+
+    for (username, address) in participants.clone().iter() {
+        if checked_in.contains(&username) {
+            continue;
+        }
+        participants.remove(username.clone());
+        let fee_paid = remove_fee_paid(env, &username);
+
+        // Retida, a taxa continua em custódia e entra no pool da liquidação
+        let refunded = policy == NoShowPolicy::Refund;
+        if refunded {
+            refund_entry(env, &username, &address, fee_paid);
+        } else {
+            no_shows.set(username.clone(), NoShow { address, forfeited_fee: fee_paid });
+        }
+        NoShowRemoved { username, refunded }.publish(env);
+    }
+
+    env.storage().persistent().set(&DataKey::Participants, &participants);
+    env.storage().persistent().set(&DataKey::NoShows, &no_shows);
+    env.storage().instance().set(&DataKey::CheckInFinalized, &true);
+
+    report_to_factory(env, LifecycleEvent::Joined(participants.len()));
+}
+
+// Bloqueia inscrições e liquidação enquanto a competição (ou a fábrica que a implantou)
+// estiver pausada. Saques e reembolsos não passam por aqui, para que os fundos sempre possam sair.
+fn require_not_paused(env: &Env) {
+    if CompetitionContract::is_paused(env.clone()) {
+        panic!("Competition is paused");
+    }
+}
+
+#[contract]
+pub struct CompetitionContract;
+
+#[contractimpl]
+impl CompetitionContract {
+    // Executado uma única vez, no mesmo deploy que cria o contrato, então não existe
+    // janela em que a competição esteja implantada mas sem admin.
+    pub fn __constructor(
+        env: Env,
+        admin: Address,
+        config: CompetitionConfig,
+        factory: Option<Address>,
+        platform_fee_bps: u32,
+    ) {
+        if config.results_deadline <= config.deadline {
+            panic!("Results deadline must be after the registration deadline");
+        }
+        if config.max_participants != 0 && config.max_participants < config.min_participants {
+            panic!("Max participants must be at least min participants");
+        }
+        expand_payout_curve(&env, &config.payout_rules);
+        let mut previous_min: Option<u32> = None;
+        for bracket in config.payout_brackets.iter() {
+            if previous_min.is_some_and(|min| bracket.min_participants <= min) {
+                panic!("Payout brackets must be in increasing order of participants");
+            }
+            if expand_payout_curve(&env, &bracket.payout_rules).len() > bracket.min_participants {
+                panic!("Payout bracket pays more places than its minimum field size");
+            }
+            previous_min = Some(bracket.min_participants);
+        }
+
+        if config.team_size == 1 {
+            panic!("Teams must have at least two players");
+        }
+        if config.fixed_prizes.len() > MAX_PAYOUT_PLACES {
+            panic!("Fixed prizes cannot pay more than 100 places");
+        }
+        for amount in config.fixed_prizes.iter() {
+            if amount <= 0 {
+                panic!("Fixed prizes must be positive");
+            }
+        }
+
+        let mut previous_until: Option<u64> = None;
+        for step in config.fee_schedule.iter() {
+            if step.fee < 0 {
+                panic!("Entry fee cannot be negative");
+            }
+            if previous_until.is_some_and(|until| step.until <= until) || step.until > config.deadline {
+                panic!("Fee schedule steps must be increasing and end by the deadline");
+            }
+            previous_until = Some(step.until);
+        }
+        if config.check_in_end != 0
+            && (config.check_in_end <= config.deadline
+                || config.check_in_end >= config.results_deadline)
+        {
+            panic!("Check-in must end between the registration and results deadlines");
+        }
+        // A taxa da plataforma é depositada na tesouraria da fábrica
+        if platform_fee_bps > 0 && factory.is_none() {
+            panic!("Platform fee requires a factory");
+        }
+        if platform_fee_bps > 10000 {
+            panic!("Platform fee cannot exceed 10000 basis points");
+        }
+        // A sobra nunca cai no admin por padrão: sem fábrica a tesouraria é informada na criação
+        let treasury = match (&config.treasury, &factory) {
+            (Some(treasury), None) => treasury.clone(),
+            (None, Some(factory)) => factory.clone(),
+            (None, None) => panic!("Treasury is required without a factory"),
+            (Some(_), Some(_)) => panic!("Factory competitions pay the platform treasury"),
+        };
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Oracle, &admin);
+        env.storage().instance().set(&DataKey::Treasury, &treasury);
+        env.storage().instance().set(&DataKey::Moderator, &admin);
+        env.storage().instance().set(&DataKey::Token, &config.token);
+        env.storage().instance().set(&DataKey::EntryFee, &config.entry_fee);
+        env.storage().instance().set(&DataKey::FeeSchedule, &config.fee_schedule);
+        env.storage().instance().set(&DataKey::PayoutCurve, &config.payout_rules);
+        env.storage().instance().set(&DataKey::PayoutBrackets, &config.payout_brackets);
+        env.storage().instance().set(&DataKey::FixedPrizes, &config.fixed_prizes);
+        env.storage().instance().set(&DataKey::RemainderPolicy, &config.remainder_policy);
+        env.storage().instance().set(&DataKey::TeamSize, &config.team_size);
+        env.storage().instance().set(&DataKey::TeamFeeMode, &config.team_fee_mode);
+        if let Some(root) = &config.allowlist_root {
+            env.storage().instance().set(&DataKey::AllowlistRoot, root);
+        }
+        env.storage().instance().set(&DataKey::AllowlistLeaf, &config.allowlist_leaf);
+        env.storage().persistent().set(&DataKey::Participants, &Map::<Symbol, Address>::new(&env));
+        env.storage().instance().set(&DataKey::IsActive, &true);
+        env.storage().instance().set(&DataKey::Deadline, &config.deadline);
+        env.storage().instance().set(&DataKey::MinParticipants, &config.min_participants);
+        env.storage().instance().set(&DataKey::MaxParticipants, &config.max_participants);
+        env.storage().instance().set(&DataKey::ResultsDeadline, &config.results_deadline);
+        env.storage().instance().set(&DataKey::CheckInEnd, &config.check_in_end);
+        env.storage().instance().set(&DataKey::NoShowPolicy, &config.no_show_policy);
+        env.storage()
+            .instance()
+            .set(&DataKey::DisqualificationPolicy, &config.disqualification_policy);
+        env.storage().instance().set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        if let Some(factory) = factory {
+            env.storage().instance().set(&DataKey::Factory, &factory);
+        }
+        env.storage().instance().set(&DataKey::PlatformFeeBps, &platform_fee_bps);
+    }
+
+    // `proof` só é usado em competições privadas; nas abertas pode ser vazio.
+    pub fn join(env: Env, participant: Address, username: Symbol, proof: Vec<BytesN<32>>) {
+        participant.require_auth();
+        require_not_paused(&env);
+        require_registration_open(&env);
+        if team_size(&env) != 0 {
+            panic!("Competition is in team mode");
+        }
+        require_allowlisted(&env, &participant, &username, &proof);
+
+        let entry_fee = current_entry_fee(&env);
+
+        if is_disqualified(&env, &participant) {
+            panic!("Participant is disqualified");
+        }
+
+        let deposited = collect_deposit(&env, &participant, entry_fee);
+
+        let mut participants: Map<Symbol, Address> =
+            env.storage().persistent().get(&DataKey::Participants).unwrap();
+        let mut waitlist = load_waitlist(&env);
+        if participants.contains_key(username.clone())
+            || waitlist.iter().any(|entry| entry.username == username)
+            || load_disqualified(&env).contains_key(username.clone())
+        {
+            panic!("Username already registered");
+        }
+
+        let mut fees_paid = load_fees_paid(&env);
+        fees_paid.set(username.clone(), deposited);
+        env.storage().persistent().set(&DataKey::FeesPaid, &fees_paid);
+        env.storage().instance().set(&DataKey::Escrow, &(escrow(&env) + deposited));
+
+        // Com o limite atingido, a inscrição entra na lista de espera com a taxa em custódia
+        let max_participants: u32 =
+            env.storage().instance().get(&DataKey::MaxParticipants).unwrap_or(0);
+        if max_participants != 0 && participants.len() >= max_participants {
+            waitlist.push_back(WaitlistEntry { username: username.clone(), address: participant });
+            env.storage().persistent().set(&DataKey::Waitlist, &waitlist);
+
+            Waitlisted { username, position: waitlist.len() - 1 }.publish(&env);
+            return;
+        }
+
+        participants.set(username, participant);
+        env.storage().persistent().set(&DataKey::Participants, &participants);
+
+        report_to_factory(&env, LifecycleEvent::Joined(participants.len()));
+    }
+
+    // Registra um time em modo time. `members` são os demais jogadores, que precisam aceitar o
+    // convite com `accept_invitation`; `shares` (vazio = divisão igual) começa pelo capitão.
+    pub fn register_team(
+        env: Env,
+        captain: Address,
+        team_name: Symbol,
+        members: Vec<Address>,
+        shares: Vec<u32>,
+        proof: Vec<BytesN<32>>,
+    ) {
+        captain.require_auth();
+        require_not_paused(&env);
+        require_registration_open(&env);
+        // Em competições privadas, a allowlist vale para o capitão ou para o nome do time; com
+        // folhas de endereço, cada membro prova a própria inclusão em `accept_invitation`
+        require_allowlisted(&env, &captain, &team_name, &proof);
+
+        let team_size = team_size(&env);
+        if team_size == 0 {
+            panic!("Competition is not in team mode");
+        }
+        if members.len() + 1 != team_size {
+            panic!("Team must have exactly team_size players");
+        }
+
+        let mut roster: Vec<Address> = vec![&env, captain.clone()];
+        roster.append(&members);
+
+        let mut teams = load_teams(&env);
+        let disqualified = load_disqualified(&env);
+        for (i, player) in roster.iter().enumerate() {
+            if roster.first_index_of(&player) != Some(i as u32) {
+                panic!("Player listed twice");
+            }
+            if teams.values().iter().any(|team| team.members.contains(&player)) {
+                panic!("Player is already on a team");
+            }
+            if is_disqualified(&env, &player) {
+                panic!("Participant is disqualified");
+            }
+        }
+
+        if !shares.is_empty() {
+            if shares.len() != team_size {
+                panic!("Shares must have one entry per player");
+            }
+            let mut total: u32 = 0;
+            for share in shares.iter() {
+                total = total.saturating_add(share);
+            }
+            if total != 10000 {
+                panic!("Shares must add up to 10000 basis points");
+            }
+        }
+
+        let participants: Map<Symbol, Address> =
+            env.storage().persistent().get(&DataKey::Participants).unwrap();
+        if participants.contains_key(team_name.clone())
+            || teams.contains_key(team_name.clone())
+            || disqualified.contains_key(team_name.clone())
+        {
+            panic!("Team name already registered");
+        }
+
+        // Em modo time o limite conta times, incluindo os que ainda aguardam membros
+        let max_participants: u32 =
+            env.storage().instance().get(&DataKey::MaxParticipants).unwrap_or(0);
+        let pending_teams =
+            teams.values().iter().filter(|team| !is_team_complete(team)).count() as u32;
+        if max_participants != 0 && participants.len() + pending_teams >= max_participants {
+            panic!("Competition is full");
+        }
+
+        let entry_fee = current_entry_fee(&env);
+        let fee_mode: TeamFeeMode = env.storage().instance().get(&DataKey::TeamFeeMode).unwrap();
+        let captain_fee = match fee_mode {
+            TeamFeeMode::CaptainPaysAll => entry_fee * team_size as i128,
+            TeamFeeMode::PerMember => entry_fee,
+        };
+        let deposited = collect_deposit(&env, &captain, captain_fee);
+
+        let mut accepted: Vec<bool> = vec![&env, true];
+        let mut contributions: Vec<i128> = vec![&env, deposited];
+        for _ in members.iter() {
+            accepted.push_back(false);
+            contributions.push_back(0);
+        }
+
+        let mut fees_paid = load_fees_paid(&env);
+        fees_paid.set(team_name.clone(), deposited);
+        env.storage().persistent().set(&DataKey::FeesPaid, &fees_paid);
+        env.storage().instance().set(&DataKey::Escrow, &(escrow(&env) + deposited));
+
+        let team = Team { captain: captain.clone(), members: roster, shares, accepted, contributions };
+        teams.set(team_name.clone(), team);
+        env.storage().persistent().set(&DataKey::Teams, &teams);
+
+        TeamRegistered { team_name, captain }.publish(&env);
+    }
+
+    // O membro convidado confirma a vaga com a própria assinatura, pagando a taxa se o time
+    // for no modo `PerMember`. Quando o último aceita, o time entra na competição. `proof` só é
+    // usado em competições privadas com folhas de endereço.
+    pub fn accept_invitation(
+        env: Env,
+        member: Address,
+        team_name: Symbol,
+        proof: Vec<BytesN<32>>,
+    ) {
+        member.require_auth();
+        require_not_paused(&env);
+        require_registration_open(&env);
+        if allowlist_leaf_kind(&env) == AllowlistLeaf::Address {
+            require_allowlisted(&env, &member, &team_name, &proof);
+        }
+
+        let mut teams = load_teams(&env);
+        let mut team = teams.get(team_name.clone()).unwrap_or_else(|| panic!("Team not found"));
+        let index = team
+            .members
+            .first_index_of(&member)
+            .unwrap_or_else(|| panic!("Not invited to this team"));
+        if team.accepted.get(index).unwrap() {
+            panic!("Invitation already accepted");
+        }
+
+        let fee_mode: TeamFeeMode = env.storage().instance().get(&DataKey::TeamFeeMode).unwrap();
+        if fee_mode == TeamFeeMode::PerMember {
+            let deposited = collect_deposit(&env, &member, current_entry_fee(&env));
+            team.contributions.set(index, deposited);
+
+            let mut fees_paid = load_fees_paid(&env);
+            let team_total = fees_paid.get(team_name.clone()).unwrap_or(0);
+            fees_paid.set(team_name.clone(), team_total + deposited);
+            env.storage().persistent().set(&DataKey::FeesPaid, &fees_paid);
+            env.storage().instance().set(&DataKey::Escrow, &(escrow(&env) + deposited));
+        }
+        team.accepted.set(index, true);
+
+        let team_complete = is_team_complete(&team);
+        let captain = team.captain.clone();
+        teams.set(team_name.clone(), team);
+        env.storage().persistent().set(&DataKey::Teams, &teams);
+
+        if team_complete {
+            let mut participants: Map<Symbol, Address> =
+                env.storage().persistent().get(&DataKey::Participants).unwrap();
+            participants.set(team_name.clone(), captain);
+            env.storage().persistent().set(&DataKey::Participants, &participants);
+
+            report_to_factory(&env, LifecycleEvent::Joined(participants.len()));
+        }
+
+        InvitationAccepted { team_name, member, team_complete }.publish(&env);
+    }
+
+    // Deposita fundos de patrocínio para cobrir os prêmios fixos. O excedente entra no pool.
+    pub fn fund_prizes(env: Env, funder: Address, amount: i128) {
+        funder.require_auth();
+
+        let is_active: bool = env.storage().instance().get(&DataKey::IsActive).unwrap();
+        if !is_active {
+            panic!("Competition is not active");
+        }
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let deposited = collect_deposit(&env, &funder, amount);
+
+        let mut sponsors = load_sponsors(&env);
+        let previous = sponsors.get(funder.clone()).unwrap_or(0);
+        sponsors.set(funder.clone(), previous + deposited);
+        env.storage().persistent().set(&DataKey::Sponsors, &sponsors);
+        env.storage().instance().set(&DataKey::Escrow, &(escrow(&env) + deposited));
+
+        PrizesFunded { funder, amount: deposited }.publish(&env);
+    }
+
+    // Recebe a sobra de uma competição com a política `RollOver`. O valor entra no pool, mas não
+    // financia prêmios fixos nem volta para quem enviou.
+    pub fn receive_roll_over(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+
+        let is_active: bool = env.storage().instance().get(&DataKey::IsActive).unwrap();
+        if !is_active {
+            panic!("Competition is not active");
+        }
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let deposited = collect_deposit(&env, &from, amount);
+        env.storage().instance().set(&DataKey::RolledOver, &(rolled_over(&env) + deposited));
+        env.storage().instance().set(&DataKey::Escrow, &(escrow(&env) + deposited));
+
+        RollOverReceived { from, amount: deposited }.publish(&env);
+    }
+
+    pub fn withdraw(env: Env, participant_address: Address) {
+        participant_address.require_auth();
+
+        let is_active: bool = env.storage().instance().get(&DataKey::IsActive).unwrap();
+        if !is_active {
+            panic!("Competition is not active");
+        }
+
+        let mut participants: Map<Symbol, Address> =
+            env.storage().persistent().get(&DataKey::Participants).unwrap();
+
+        let mut username_to_remove: Option<Symbol> = None;
+        for (username, address) in participants.iter() {
+            if address == participant_address {
+                username_to_remove = Some(username);
+                break;
+            }
+        }
+
+        let mut waitlist = load_waitlist(&env);
+
+        if let Some(username) = username_to_remove {
+            participants.remove(username.clone());
+
+            let mut checked_in = load_checked_in(&env);
+            if let Some(index) = checked_in.first_index_of(&username) {
+                checked_in.remove(index);
+                env.storage().persistent().set(&DataKey::CheckedIn, &checked_in);
+            }
+
+            promote_from_waitlist(&env, &mut participants);
+            env.storage().persistent().set(&DataKey::Participants, &participants);
+
+            let fee_paid = remove_fee_paid(&env, &username);
+            refund_entry(&env, &username, &participant_address, fee_paid);
+
+            report_to_factory(&env, LifecycleEvent::Joined(participants.len()));
+        } else if let Some(team_name) = load_teams(&env)
+            .iter()
+            .find(|(_, team)| team.captain == participant_address && !is_team_complete(team))
+            .map(|(team_name, _)| team_name)
+        {
+            // O capitão pode desfazer um time que ainda aguarda membros
+            let fee_paid = remove_fee_paid(&env, &team_name);
+            refund_entry(&env, &team_name, &participant_address, fee_paid);
+        } else if let Some(index) =
+            waitlist.iter().position(|entry| entry.address == participant_address)
+        {
+            let entry = waitlist.get(index as u32).unwrap();
+            waitlist.remove(index as u32);
+            env.storage().persistent().set(&DataKey::Waitlist, &waitlist);
+
+            let fee_paid = remove_fee_paid(&env, &entry.username);
+            release(&env, &participant_address, fee_paid);
+        } else {
+            panic!("Participant not found");
+        }
+    }
+
+    // Confirma presença na janela entre o fim das inscrições e o início da competição.
+    pub fn check_in(env: Env, participant_address: Address) {
+        participant_address.require_auth();
+
+        let check_in_end = check_in_end(&env);
+        if check_in_end == 0 {
+            panic!("Competition has no check-in");
+        }
+        let deadline: u64 = env.storage().instance().get(&DataKey::Deadline).unwrap();
+        let now = env.ledger().timestamp();
+        if now <= deadline || now > check_in_end {
+            panic!("Check-in window is not open");
+        }
+
+        let participants: Map<Symbol, Address> =
+            env.storage().persistent().get(&DataKey::Participants).unwrap();
+        let mut username_found: Option<Symbol> = None;
+        for (username, address) in participants.iter() {
+            if address == participant_address {
+                username_found = Some(username);
+                break;
+            }
+        }
+        let username = username_found.unwrap_or_else(|| panic!("Participant not found"));
+
+        let mut checked_in = load_checked_in(&env);
+        if checked_in.contains(&username) {
+            panic!("Already checked in");
+        }
+        checked_in.push_back(username.clone());
+        env.storage().persistent().set(&DataKey::CheckedIn, &checked_in);
+
+        CheckedIn { username }.publish(&env);
+    }
+
+    // Qualquer um pode acionar a remoção dos ausentes depois que a janela de check-in fecha;
+    // `distribute_prizes` também a executa se ainda não tiver rodado.
+    pub fn finalize_check_in(env: Env) {
+        finalize_check_in(&env);
+    }
+
+    // Remove o jogador da elegibilidade a prêmios. Pode ser chamado pelo admin ou pelo moderador;
+    // a taxa dele é devolvida ou fica no pool conforme a política da competição.
+    pub fn disqualify(env: Env, authority: Address, username: Symbol, reason: SdkString) {
+        authority.require_auth();
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if authority != admin && authority != moderator(&env) {
+            panic!("Only the admin or moderator can disqualify");
+        }
+
+        let is_active: bool = env.storage().instance().get(&DataKey::IsActive).unwrap();
+        if !is_active {
+            panic!("Competition is not active");
+        }
+
+        let mut participants: Map<Symbol, Address> =
+            env.storage().persistent().get(&DataKey::Participants).unwrap();
+        let address = participants
+            .get(username.clone())
+            .unwrap_or_else(|| panic!("Participant not found"));
+        participants.remove(username.clone());
+        promote_from_waitlist(&env, &mut participants);
+        env.storage().persistent().set(&DataKey::Participants, &participants);
+
+        let mut checked_in = load_checked_in(&env);
+        if let Some(index) = checked_in.first_index_of(&username) {
+            checked_in.remove(index);
+            env.storage().persistent().set(&DataKey::CheckedIn, &checked_in);
+        }
+
+        let policy: DisqualificationPolicy = env
+            .storage()
+            .instance()
+            .get(&DataKey::DisqualificationPolicy)
+            .unwrap_or(DisqualificationPolicy::Refund);
+        // O reembolso tira o time do registro, então os membros são lidos antes
+        let members = load_teams(&env)
+            .get(username.clone())
+            .map(|team| team.members)
+            .unwrap_or_else(|| Vec::new(&env));
+
+        // Retida, a taxa continua em custódia e entra no pool da liquidação
+        let fee_paid = remove_fee_paid(&env, &username);
+        let refunded = policy == DisqualificationPolicy::Refund;
+        let forfeited_fee = if refunded {
+            refund_entry(&env, &username, &address, fee_paid);
+            0
+        } else {
+            fee_paid
+        };
+
+        let mut disqualified = load_disqualified(&env);
+        disqualified.set(
+            username.clone(),
+            Disqualification { address, members, reason: reason.clone(), refunded, forfeited_fee },
+        );
+        env.storage().persistent().set(&DataKey::Disqualified, &disqualified);
+
+        PlayerDisqualified { username, reason, refunded }.publish(&env);
+
+        report_to_factory(&env, LifecycleEvent::Joined(participants.len()));
+    }
+
+    pub fn distribute_prizes(env: Env, leaderboard: Vec<Symbol>) {
+        let oracle: Address = env.storage().instance().get(&DataKey::Oracle).unwrap();
+        oracle.require_auth();
+        require_not_paused(&env);
+
+        let results_deadline: u64 = env.storage().instance().get(&DataKey::ResultsDeadline).unwrap();
+        if env.ledger().timestamp() > results_deadline {
+            panic!("Results deadline has passed");
+        }
+        // Sem o mínimo de participantes os jogadores podem pedir reembolso; não há o que liquidar
+        if Self::is_refundable(env.clone()) {
+            panic!("Competition is refundable");
+        }
+
+        finalize_check_in(&env);
 
-#[contractimpl]
-impl CompetitionContract {
-    pub fn initialize(
-        env: Env,
-        admin: Address,
-        entry_fee: i128,
-        payout_rules: Vec<u32>,
-        deadline: u64,
-        min_participants: u32,
-    ) {
-        if env.storage().instance().has(&DataKey::Admin) {
-            panic!("Contract already initialized");
+        env.storage().instance().set(&DataKey::IsActive, &false);
+        refund_waitlist(&env);
+        refund_pending_teams(&env);
+
+        let participants: Map<Symbol, Address> = env.storage().persistent().get(&DataKey::Participants).unwrap();
+        let payout_rules = payout_rules_for(&env, participants.len());
+
+        // Com a lista de espera devolvida, o que resta em custódia são os depósitos registrados
+        // dos participantes, as taxas retidas, o financiamento dos patrocinadores e a sobra
+        // recebida de outra competição
+        let total_prize_pool = escrow(&env);
+
+        let remainder_policy = remainder_policy(&env);
+
+        if total_prize_pool <= 0 {
+            let receipt = SettlementReceipt {
+                total_prize_pool: 0,
+                platform_fee: 0,
+                paid_out: 0,
+                sponsor_refund: 0,
+                remainder: 0,
+                remainder_policy,
+                applied_policy: remainder_policy,
+                remainder_recipient: None,
+                settled_at: env.ledger().timestamp(),
+            };
+            env.storage().instance().set(&DataKey::SettlementReceipt, &receipt);
+
+            report_to_factory(&env, LifecycleEvent::Settled(0));
+            return;
         }
 
-        env.storage().instance().set(&DataKey::Admin, &admin);
-        env.storage().instance().set(&DataKey::EntryFee, &entry_fee);
-        env.storage().instance().set(&DataKey::PayoutRules, &payout_rules);
-        env.storage().instance().set(&DataKey::Participants, &Map::<Symbol, Address>::new(&env));
-        env.storage().instance().set(&DataKey::IsActive, &true);
-        env.storage().instance().set(&DataKey::Deadline, &deadline);
-        env.storage().instance().set(&DataKey::MinParticipants, &min_participants);
-    }
+        // A taxa da plataforma incide só sobre o dinheiro dos jogadores desta competição (a sobra
+        // recebida já pagou a taxa na de origem) e sai antes dos prêmios
+        let platform_fee_bps: u32 =
+            env.storage().instance().get(&DataKey::PlatformFeeBps).unwrap_or(0);
+        let players_total = total_prize_pool - sponsored_total(&env) - rolled_over(&env);
+        let platform_fee = (players_total * platform_fee_bps as i128) / 10000;
+        let prize_pool = total_prize_pool - platform_fee;
 
-    pub fn join(env: Env, participant: Address, username: Symbol) {
-        participant.require_auth();
+        // Os prêmios fixos saem primeiro; a tabela percentual divide o que sobrar
+        let fixed_prizes = fixed_prizes(&env);
+        let fixed_total = fixed_prizes_total(&env);
+        if prize_pool < fixed_total {
+            panic!("Escrow cannot cover the fixed prizes");
+        }
+        let surplus = prize_pool - fixed_total;
+        let places = payout_rules.len().max(fixed_prizes.len());
+
+        if platform_fee > 0 {
+            let factory: Address = env.storage().instance().get(&DataKey::Factory).unwrap();
+            deposit_with_factory(&env, &factory, platform_fee);
+        }
+
+        // Calcula os prêmios antes de pagar, já que a sobra pode ser somada a eles
+        let mut pool_rank: u32 = 0;
+        let mut winners: Vec<(Symbol, Address, i128)> = Vec::new(&env);
+        let mut total_paid_out: i128 = 0;
+
+        for username in leaderboard.iter() {
+            if pool_rank >= places {
+                break;
+            }
+
+            // Desclassificados não estão mais em `participants`: são ignorados e a posição
+            // vai para o próximo elegível do leaderboard
+            if let Some(winner_address) = participants.get(username.clone()) {
+                let payout_percentage = payout_rules.get(pool_rank).unwrap_or(0);
+                let payout_amount = fixed_prizes.get(pool_rank).unwrap_or(0)
+                    + (surplus * payout_percentage as i128) / 10000;
+
+                if payout_amount > 0 {
+                    winners.push_back((username, winner_address, payout_amount));
+                    total_paid_out += payout_amount;
+                }
+
+                pool_rank += 1;
+            }
+        }
+
+        // Posições com prêmio fixo que ficaram sem vencedor não pertencem ao operador: o valor
+        // volta para quem financiou
+        let mut sponsor_refund: i128 = 0;
+        for place in pool_rank..fixed_prizes.len() {
+            sponsor_refund += fixed_prizes.get(place).unwrap();
+        }
+        refund_unpaid_fixed_prizes(&env, sponsor_refund);
+
+        let remainder = prize_pool.saturating_sub(total_paid_out + sponsor_refund);
+        let mut remainder_recipient: Option<Address> = None;
+        let mut applied_policy = remainder_policy;
+        if remainder > 0 {
+            // Sem vencedores ou sem competição ligada ainda ativa, a sobra cai para a tesouraria
+            let next_competition: Option<Address> = env
+                .storage()
+                .instance()
+                .get(&DataKey::NextCompetition)
+                .filter(|next| accepts_roll_over(&env, next));
+            applied_policy = match remainder_policy {
+                RemainderPolicy::ProRataWinners | RemainderPolicy::FirstPlace
+                    if winners.is_empty() =>
+                {
+                    RemainderPolicy::Treasury
+                }
+                RemainderPolicy::RollOver if next_competition.is_none() => RemainderPolicy::Treasury,
+                policy => policy,
+            };
+            match applied_policy {
+                RemainderPolicy::Admin => {
+                    let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+                    release(&env, &admin, remainder);
+                    remainder_recipient = Some(admin);
+                }
+                RemainderPolicy::Treasury => {
+                    remainder_recipient = Some(pay_treasury(&env, remainder));
+                }
+                RemainderPolicy::FirstPlace => {
+                    let (first_username, first_address, first_amount) = winners.get(0).unwrap();
+                    winners.set(0, (first_username, first_address.clone(), first_amount + remainder));
+                    remainder_recipient = Some(first_address);
+                }
+                RemainderPolicy::ProRataWinners => {
+                    // A poeira da divisão proporcional fica com o primeiro colocado
+                    let mut distributed: i128 = 0;
+                    for i in 0..winners.len() {
+                        let (username, address, amount) = winners.get(i).unwrap();
+                        let share = remainder * amount / total_paid_out;
+                        winners.set(i, (username, address, amount + share));
+                        distributed += share;
+                    }
+                    let (first_username, first_address, first_amount) = winners.get(0).unwrap();
+                    winners.set(
+                        0,
+                        (first_username, first_address, first_amount + remainder - distributed),
+                    );
+                }
+                RemainderPolicy::RollOver => {
+                    let next_competition = next_competition.unwrap();
+                    roll_over(&env, &next_competition, remainder);
+                    remainder_recipient = Some(next_competition);
+                }
+            }
+        }
+
+        for (username, winner_address, payout_amount) in winners.iter() {
+            pay_entry(&env, &username, &winner_address, payout_amount);
+        }
+
+        let receipt = SettlementReceipt {
+            total_prize_pool,
+            platform_fee,
+            paid_out: total_paid_out,
+            sponsor_refund,
+            remainder,
+            remainder_policy,
+            applied_policy,
+            remainder_recipient,
+            settled_at: env.ledger().timestamp(),
+        };
+        env.storage().instance().set(&DataKey::SettlementReceipt, &receipt);
+
+        PrizesDistributed { total_prize_pool, paid_out: total_paid_out, remainder, remainder_policy }
+            .publish(&env);
+
+        report_to_factory(&env, LifecycleEvent::Settled(total_prize_pool));
+    }
 
+    pub fn refund_all(env: Env) {
         let is_active: bool = env.storage().instance().get(&DataKey::IsActive).unwrap();
         if !is_active {
-            panic!("Competition is closed");
+            panic!("Competition is not active");
         }
 
-        let entry_fee: i128 = env.storage().instance().get(&DataKey::EntryFee).unwrap();
+        let participants: Map<Symbol, Address> =
+            env.storage().persistent().get(&DataKey::Participants).unwrap();
+        let fees_paid = load_fees_paid(&env);
 
-        let xlm_wrapper_address = Address::from_string(&SdkString::from_str(
-            &env,
-            "CDLZXA64VFPATL2I4QN5VTO762U2AF2L66ZNFP3H34N3G45B3SGH4YTR",
-        ));
-        let token_client = token::Client::new(&env, &xlm_wrapper_address);
+        if Self::is_refundable(env.clone()) {
+            env.storage().instance().set(&DataKey::IsActive, &false);
 
-        token_client.transfer(&participant, &env.current_contract_address(), &entry_fee);
+            for (username, participant_address) in participants.iter() {
+                let fee_paid = fee_paid(&env, &fees_paid, &username);
+                refund_entry(&env, &username, &participant_address, fee_paid);
+            }
+            refund_waitlist(&env);
+            refund_pending_teams(&env);
+            refund_sponsors(&env);
+            release_rolled_over(&env);
+            return_forfeited_fees(&env);
 
-        let mut participants: Map<Symbol, Address> =
-            env.storage().instance().get(&DataKey::Participants).unwrap();
-        if participants.contains_key(username.clone()) {
-            panic!("Username already registered");
+            report_to_factory(&env, LifecycleEvent::Refunded);
+        } else {
+            panic!("Refund conditions not met");
         }
+    }
 
-        participants.set(username, participant);
-        env.storage().instance().set(&DataKey::Participants, &participants);
+    // Cancelamento pelo admin antes da liquidação: encerra a competição e devolve todas as inscrições.
+    pub fn cancel(env: Env) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let is_active: bool = env.storage().instance().get(&DataKey::IsActive).unwrap();
+        if !is_active {
+            panic!("Competition is not active");
+        }
+        env.storage().instance().set(&DataKey::IsActive, &false);
+
+        let participants: Map<Symbol, Address> =
+            env.storage().persistent().get(&DataKey::Participants).unwrap();
+        let fees_paid = load_fees_paid(&env);
+
+        for (username, participant_address) in participants.iter() {
+            let fee_paid = fee_paid(&env, &fees_paid, &username);
+            refund_entry(&env, &username, &participant_address, fee_paid);
+        }
+        refund_waitlist(&env);
+        refund_pending_teams(&env);
+        refund_sponsors(&env);
+        release_rolled_over(&env);
+        return_forfeited_fees(&env);
+
+        report_to_factory(&env, LifecycleEvent::Cancelled);
     }
 
-    pub fn withdraw(env: Env, participant_address: Address) {
+    // Permite que um participante recupere a própria inscrição quando a competição
+    // se torna reembolsável (ex.: o admin não publicou resultados até `results_deadline`).
+    pub fn claim_refund(env: Env, participant_address: Address) {
         participant_address.require_auth();
 
         let is_active: bool = env.storage().instance().get(&DataKey::IsActive).unwrap();
         if !is_active {
             panic!("Competition is not active");
         }
+        if !Self::is_refundable(env.clone()) {
+            panic!("Refund conditions not met");
+        }
 
         let mut participants: Map<Symbol, Address> =
-            env.storage().instance().get(&DataKey::Participants).unwrap();
-        let entry_fee: i128 = env.storage().instance().get(&DataKey::EntryFee).unwrap();
+            env.storage().persistent().get(&DataKey::Participants).unwrap();
 
         let mut username_to_remove: Option<Symbol> = None;
         for (username, address) in participants.iter() {
@@ -92,99 +1803,430 @@ impl CompetitionContract {
         }
 
         if let Some(username) = username_to_remove {
-            participants.remove(username);
-            env.storage().instance().set(&DataKey::Participants, &participants);
+            participants.remove(username.clone());
+            env.storage().persistent().set(&DataKey::Participants, &participants);
+
+            let fee_paid = remove_fee_paid(&env, &username);
 
-            let xlm_wrapper_address = Address::from_string(&SdkString::from_str(
-                &env,
-                "CDLZXA64VFPATL2I4QN5VTO762U2AF2L66ZNFP3H34N3G45B3SGH4YTR",
-            ));
-            let token_client = token::Client::new(&env, &xlm_wrapper_address);
+            refund_entry(&env, &username, &participant_address, fee_paid);
 
-            token_client.transfer(&env.current_contract_address(), &participant_address, &entry_fee);
+            // A competição só conta como reembolsada quando não resta nada em custódia
+            if escrow(&env) == 0 {
+                report_to_factory(&env, LifecycleEvent::Refunded);
+            } else {
+                report_to_factory(&env, LifecycleEvent::Joined(participants.len()));
+            }
         } else {
             panic!("Participant not found");
         }
     }
 
-    pub fn distribute_prizes(env: Env, leaderboard: Vec<Symbol>) {
+    // A competição é reembolsável se as inscrições fecharam sem o mínimo de participantes,
+    // ou se o prazo de resultados passou sem que `distribute_prizes` fosse chamado.
+    pub fn is_refundable(env: Env) -> bool {
+        let is_active: bool = env.storage().instance().get(&DataKey::IsActive).unwrap();
+        if !is_active {
+            return false;
+        }
+
+        let deadline: u64 = env.storage().instance().get(&DataKey::Deadline).unwrap();
+        let results_deadline: u64 = env.storage().instance().get(&DataKey::ResultsDeadline).unwrap();
+        let min_participants: u32 = env.storage().instance().get(&DataKey::MinParticipants).unwrap();
+        let current_timestamp = env.ledger().timestamp();
+
+        // Com check-in, o mínimo só é avaliado quando a janela de check-in fecha
+        let field_closes_at = match check_in_end(&env) {
+            0 => deadline,
+            check_in_end => check_in_end,
+        };
+
+        (current_timestamp > field_closes_at && field_size(&env) < min_participants)
+            || current_timestamp > results_deadline
+    }
+
+    // Primeira etapa da troca de admin: o admin atual indica o sucessor.
+    pub fn propose_admin(env: Env, new_admin: Address) {
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
         admin.require_auth();
 
-        env.storage().instance().set(&DataKey::IsActive, &false);
+        env.storage().instance().set(&DataKey::PendingAdmin, &new_admin);
 
-        let participants: Map<Symbol, Address> = env.storage().instance().get(&DataKey::Participants).unwrap();
-        let payout_rules: Vec<u32> = env.storage().instance().get(&DataKey::PayoutRules).unwrap();
-        let entry_fee: i128 = env.storage().instance().get(&DataKey::EntryFee).unwrap();
-        let contract_address = env.current_contract_address();
+        AdminProposed { current: admin, proposed: new_admin }.publish(&env);
+    }
 
-        let total_prize_pool = (participants.len() as i128).saturating_mul(entry_fee);
+    // Segunda etapa: o endereço indicado aceita e passa a ser o admin.
+    pub fn accept_admin(env: Env) {
+        let pending_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .unwrap_or_else(|| panic!("No pending admin"));
+        pending_admin.require_auth();
 
-        if total_prize_pool <= 0 {
-            return;
+        let previous: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        env.storage().instance().set(&DataKey::Admin, &pending_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        // Os papéis que ainda estavam com o admin anterior (o padrão do construtor) passam
+        // para o novo admin, para que a chave antiga não continue publicando resultados
+        for (role, key) in [
+            (symbol_short!("oracle"), DataKey::Oracle),
+            (symbol_short!("treasury"), DataKey::Treasury),
+            (symbol_short!("moderator"), DataKey::Moderator),
+        ] {
+            if env.storage().instance().get::<_, Address>(&key) == Some(previous.clone()) {
+                env.storage().instance().set(&key, &pending_admin);
+
+                RoleRotated { role, previous: previous.clone(), new_holder: pending_admin.clone() }
+                    .publish(&env);
+            }
         }
 
-        let xlm_wrapper_address = Address::from_string(&SdkString::from_str(
-            &env,
-            "CDLZXA64VFPATL2I4QN5VTO762U2AF2L66ZNFP3H34N3G45B3SGH4YTR",
-        ));
-        let token_client = token::Client::new(&env, &xlm_wrapper_address);
+        AdminTransferred { previous, new_admin: pending_admin }.publish(&env);
+    }
 
-        let mut pool_rank: u32 = 0;
-        let mut total_paid_out: i128 = 0;
+    // O oráculo é quem envia o leaderboard final em `distribute_prizes`.
+    pub fn set_oracle(env: Env, new_oracle: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
 
-        for username in leaderboard.iter() {
-            if pool_rank >= payout_rules.len() {
-                break;
-            }
+        let previous: Address = env.storage().instance().get(&DataKey::Oracle).unwrap();
+        env.storage().instance().set(&DataKey::Oracle, &new_oracle);
 
-            if let Some(winner_address) = participants.get(username) {
-                let payout_percentage = payout_rules.get(pool_rank).unwrap();
-                let payout_amount = (total_prize_pool * payout_percentage as i128) / 10000;
+        RoleRotated { role: symbol_short!("oracle"), previous, new_holder: new_oracle }.publish(&env);
+    }
 
-                if payout_amount > 0 {
-                    token_client.transfer(&contract_address, &winner_address, &payout_amount);
-                    total_paid_out += payout_amount;
-                }
+    // A tesouraria recebe o saldo que sobra após o pagamento dos prêmios. Competições de uma
+    // fábrica sempre pagam a tesouraria da plataforma.
+    pub fn set_treasury(env: Env, new_treasury: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
 
-                pool_rank += 1;
-            }
+        if env.storage().instance().has(&DataKey::Factory) {
+            panic!("Competition pays the platform treasury");
         }
 
-        let remaining_balance = total_prize_pool.saturating_sub(total_paid_out);
-        if remaining_balance > 0 {
-            token_client.transfer(&contract_address, &admin, &remaining_balance);
-        }
+        let previous: Address = env.storage().instance().get(&DataKey::Treasury).unwrap();
+        env.storage().instance().set(&DataKey::Treasury, &new_treasury);
+
+        RoleRotated { role: symbol_short!("treasury"), previous, new_holder: new_treasury }
+            .publish(&env);
     }
 
-    pub fn refund_all(env: Env) {
-        let is_active: bool = env.storage().instance().get(&DataKey::IsActive).unwrap();
-        if !is_active {
-            panic!("Competition is not active");
+    // O moderador pode desclassificar jogadores, além do admin.
+    pub fn set_moderator(env: Env, new_moderator: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let previous = moderator(&env);
+        env.storage().instance().set(&DataKey::Moderator, &new_moderator);
+
+        RoleRotated { role: symbol_short!("moderator"), previous, new_holder: new_moderator }
+            .publish(&env);
+    }
+
+    // Liga a competição que recebe a sobra com a política `RollOver`. Ela precisa usar o mesmo token.
+    pub fn set_next_competition(env: Env, next_competition: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if next_competition == env.current_contract_address() {
+            panic!("Competition cannot roll over into itself");
+        }
+        let token_address: Address = env.storage().instance().get(&DataKey::Token).unwrap();
+        let next_token: Address =
+            env.invoke_contract(&next_competition, &Symbol::new(&env, "get_token"), Vec::new(&env));
+        if next_token != token_address {
+            panic!("Next competition must use the same token");
         }
 
+        env.storage().instance().set(&DataKey::NextCompetition, &next_competition);
+
+        NextCompetitionLinked { next_competition }.publish(&env);
+    }
+
+    // Troca a raiz da allowlist (ou torna a competição aberta com `None`) enquanto as
+    // inscrições estão abertas. Quem já entrou continua inscrito.
+    pub fn set_allowlist_root(env: Env, new_root: Option<BytesN<32>>) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
         let deadline: u64 = env.storage().instance().get(&DataKey::Deadline).unwrap();
-        let min_participants: u32 = env.storage().instance().get(&DataKey::MinParticipants).unwrap();
+        if env.ledger().timestamp() > deadline {
+            panic!("Registration is closed");
+        }
+
+        let previous: Option<BytesN<32>> = env.storage().instance().get(&DataKey::AllowlistRoot);
+        match &new_root {
+            Some(root) => env.storage().instance().set(&DataKey::AllowlistRoot, root),
+            None => env.storage().instance().remove(&DataKey::AllowlistRoot),
+        }
+
+        AllowlistRootRotated { previous, new_root }.publish(&env);
+    }
+
+    pub fn pause(env: Env) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Paused, &true);
+
+        PauseChanged { paused: true }.publish(&env);
+    }
+
+    pub fn unpause(env: Env) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Paused, &false);
+
+        PauseChanged { paused: false }.publish(&env);
+    }
+
+    // Considera tanto a pausa desta competição quanto a pausa global da fábrica.
+    pub fn is_paused(env: Env) -> bool {
+        let paused: bool = env.storage().instance().get(&DataKey::Paused).unwrap_or(false);
+        if paused {
+            return true;
+        }
+
+        match env.storage().instance().get::<_, Address>(&DataKey::Factory) {
+            Some(factory) => {
+                env.invoke_contract(&factory, &Symbol::new(&env, "is_paused"), Vec::new(&env))
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_active(env: Env) -> bool {
+        env.storage().instance().get(&DataKey::IsActive).unwrap()
+    }
+
+    pub fn get_token(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Token).unwrap()
+    }
+
+    // Taxa cobrada de quem se inscrever agora, de acordo com a tabela de taxas.
+    pub fn get_entry_fee(env: Env) -> i128 {
+        current_entry_fee(&env)
+    }
+
+    pub fn get_fee_schedule(env: Env) -> Vec<FeeStep> {
+        env.storage()
+            .instance()
+            .get(&DataKey::FeeSchedule)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    // Tabela em pontos-base que `distribute_prizes` usaria com o campo atual.
+    pub fn get_payout_table(env: Env) -> Vec<u32> {
         let participants: Map<Symbol, Address> =
-            env.storage().instance().get(&DataKey::Participants).unwrap();
-        let entry_fee: i128 = env.storage().instance().get(&DataKey::EntryFee).unwrap();
-        let current_timestamp = env.ledger().timestamp();
+            env.storage().persistent().get(&DataKey::Participants).unwrap();
+        payout_rules_for(&env, participants.len())
+    }
 
-        if current_timestamp > deadline && participants.len() < min_participants {
-            env.storage().instance().set(&DataKey::IsActive, &false);
+    pub fn get_allowlist_root(env: Env) -> Option<BytesN<32>> {
+        env.storage().instance().get(&DataKey::AllowlistRoot)
+    }
+
+    pub fn get_team_size(env: Env) -> u32 {
+        team_size(&env)
+    }
+
+    pub fn get_team(env: Env, team_name: Symbol) -> Option<Team> {
+        load_teams(&env).get(team_name)
+    }
+
+    pub fn get_remainder_policy(env: Env) -> RemainderPolicy {
+        remainder_policy(&env)
+    }
+
+    pub fn get_next_competition(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::NextCompetition)
+    }
+
+    pub fn get_settlement_receipt(env: Env) -> Option<SettlementReceipt> {
+        env.storage().instance().get(&DataKey::SettlementReceipt)
+    }
+
+    pub fn get_fixed_prizes(env: Env) -> Vec<i128> {
+        fixed_prizes(&env)
+    }
+
+    pub fn get_sponsored_total(env: Env) -> i128 {
+        sponsored_total(&env)
+    }
+
+    // Sobra recebida de outras competições com a política `RollOver`.
+    pub fn get_rolled_over(env: Env) -> i128 {
+        rolled_over(&env)
+    }
+
+    pub fn get_payout_brackets(env: Env) -> Vec<PayoutBracket> {
+        env.storage()
+            .instance()
+            .get(&DataKey::PayoutBrackets)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    pub fn get_escrow_total(env: Env) -> i128 {
+        escrow(&env)
+    }
+
+    pub fn get_fee_paid(env: Env, username: Symbol) -> Option<i128> {
+        load_fees_paid(&env).get(username)
+    }
+
+    pub fn get_checked_in(env: Env) -> Vec<Symbol> {
+        load_checked_in(&env)
+    }
+
+    pub fn get_no_show(env: Env, username: Symbol) -> Option<NoShow> {
+        load_no_shows(&env).get(username)
+    }
+
+    pub fn get_max_participants(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::MaxParticipants).unwrap_or(0)
+    }
+
+    // Lista de espera em ordem de chegada; o primeiro é promovido quando uma vaga abre.
+    pub fn get_waitlist(env: Env) -> Vec<WaitlistEntry> {
+        load_waitlist(&env)
+    }
+
+    pub fn get_platform_fee_bps(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::PlatformFeeBps).unwrap_or(0)
+    }
+
+    pub fn get_factory(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Factory)
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    pub fn get_moderator(env: Env) -> Address {
+        moderator(&env)
+    }
+
+    pub fn get_disqualification(env: Env, username: Symbol) -> Option<Disqualification> {
+        load_disqualified(&env).get(username)
+    }
+
+    pub fn get_oracle(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Oracle).unwrap()
+    }
+
+    pub fn get_treasury(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Treasury).unwrap()
+    }
+
+    // Troca o código do contrato mantendo endereço e fundos. Depois do upgrade,
+    // o admin deve chamar `migrate` para converter o armazenamento para o novo layout.
+    // Competições implantadas por uma fábrica só mudam de código por `factory_upgrade`, para
+    // que o hash registrado na fábrica seja confiável.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if env.storage().instance().has(&DataKey::Factory) {
+            panic!("Competition is upgraded by its factory");
+        }
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+        Upgraded { new_wasm_hash }.publish(&env);
+    }
+
+    // Upgrade disparado pela fábrica que implantou esta competição (`upgrade_children`).
+    pub fn factory_upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        let factory: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Factory)
+            .unwrap_or_else(|| panic!("Competition was not deployed by a factory"));
+        factory.require_auth();
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+        Upgraded { new_wasm_hash }.publish(&env);
+    }
+
+    // Converte o armazenamento de versões anteriores para `SCHEMA_VERSION`, uma versão por vez.
+    // Exige a mesma autorização do upgrade, como na fábrica: o admin, ou a fábrica nas
+    // competições implantadas por ela (`upgrade_children` migra logo depois do upgrade).
+    pub fn migrate(env: Env) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        match env.storage().instance().get::<_, Address>(&DataKey::Factory) {
+            Some(factory) => factory.require_auth(),
+            None => admin.require_auth(),
+        }
 
-            let xlm_wrapper_address = Address::from_string(&SdkString::from_str(
-                &env,
-                "CDLZXA64VFPATL2I4QN5VTO762U2AF2L66ZNFP3H34N3G45B3SGH4YTR",
-            ));
-            let token_client = token::Client::new(&env, &xlm_wrapper_address);
-            let contract_address = env.current_contract_address();
+        let from_version = Self::get_schema_version(env.clone());
+        if from_version >= SCHEMA_VERSION {
+            panic!("Nothing to migrate");
+        }
+
+        let mut version = from_version;
+        if version == 1 {
+            let participants: Map<Symbol, Address> = env
+                .storage()
+                .instance()
+                .get(&DataKey::Participants)
+                .unwrap_or_else(|| Map::new(&env));
+            env.storage().instance().remove(&DataKey::Participants);
+            env.storage().persistent().set(&DataKey::Participants, &participants);
 
-            for (_username, participant_address) in participants.iter() {
-                token_client.transfer(&contract_address, &participant_address, &entry_fee);
+            if !env.storage().instance().has(&DataKey::Oracle) {
+                env.storage().instance().set(&DataKey::Oracle, &admin);
             }
-        } else {
-            panic!("Refund conditions not met");
+            if !env.storage().instance().has(&DataKey::Treasury) {
+                env.storage().instance().set(&DataKey::Treasury, &admin);
+            }
+            if !env.storage().instance().has(&DataKey::ResultsDeadline) {
+                let deadline: u64 = env.storage().instance().get(&DataKey::Deadline).unwrap();
+                let results_deadline = deadline.saturating_add(DEFAULT_RESULTS_WINDOW);
+                env.storage().instance().set(&DataKey::ResultsDeadline, &results_deadline);
+            }
+            version = 2;
+        }
+        if version == 2 {
+            let legacy_token = Address::from_string(&SdkString::from_str(&env, LEGACY_XLM_TOKEN));
+            env.storage().instance().set(&DataKey::Token, &legacy_token);
+            version = 3;
+        }
+        if version == 3 {
+            // Inscrições antigas pagaram a taxa base; a custódia parte da soma delas
+            let participants: Map<Symbol, Address> =
+                env.storage().persistent().get(&DataKey::Participants).unwrap();
+            let fees_paid = load_fees_paid(&env);
+            let mut total: i128 = env.storage().instance().get(&DataKey::ForfeitedPool).unwrap_or(0);
+            for username in participants.keys().iter() {
+                total += fee_paid(&env, &fees_paid, &username);
+            }
+            for entry in load_waitlist(&env).iter() {
+                total += fee_paid(&env, &fees_paid, &entry.username);
+            }
+            let is_active: bool = env.storage().instance().get(&DataKey::IsActive).unwrap();
+            env.storage().instance().set(&DataKey::Escrow, &if is_active { total } else { 0 });
+            version = 4;
         }
+
+        env.storage().instance().set(&DataKey::SchemaVersion, &version);
+
+        Migrated { from_version, to_version: version }.publish(&env);
     }
-}
\ No newline at end of file
+
+    // Contratos implantados antes do versionamento não têm a chave e são tratados como v1.
+    pub fn get_schema_version(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::SchemaVersion).unwrap_or(1)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,1513 @@
+#![cfg(test)]
+extern crate std;
+
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
+    token::{StellarAssetClient, TokenClient},
+    vec, xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, Map, String as SdkString, Symbol, Vec,
+};
+
+use crate::{
+    expand_payout_curve, AllowlistLeaf, CompetitionConfig, CompetitionContract, CompetitionContractClient, DataKey,
+    DisqualificationPolicy, FeeStep, LifecycleEvent, NoShowPolicy, PayoutBracket, PayoutCurve, RemainderPolicy, TeamFeeMode,
+    DEFAULT_RESULTS_WINDOW, LEGACY_XLM_TOKEN,
+};
+
+const ENTRY_FEE: i128 = 100;
+const DEADLINE: u64 = 1_000;
+
+fn config(env: &Env, token: &Address) -> CompetitionConfig {
+    CompetitionConfig {
+        token: token.clone(),
+        entry_fee: ENTRY_FEE,
+        fee_schedule: Vec::new(env),
+        payout_rules: PayoutCurve::WinnerTakesAll,
+        payout_brackets: Vec::new(env),
+        fixed_prizes: Vec::new(env),
+        remainder_policy: RemainderPolicy::Treasury,
+        treasury: Some(Address::generate(env)),
+        team_size: 0,
+        team_fee_mode: TeamFeeMode::CaptainPaysAll,
+        allowlist_root: None,
+        allowlist_leaf: AllowlistLeaf::Address,
+        deadline: DEADLINE,
+        min_participants: 1,
+        max_participants: 0,
+        results_deadline: DEADLINE * 2,
+        check_in_end: 0,
+        no_show_policy: NoShowPolicy::Refund,
+        disqualification_policy: DisqualificationPolicy::Refund,
+    }
+}
+
+// Implanta uma competição sem fábrica, com a configuração padrão ajustada por `customize`.
+fn deploy<'a>(
+    env: &Env,
+    customize: impl FnOnce(&mut CompetitionConfig),
+) -> (CompetitionContractClient<'a>, Address, Address) {
+    let admin = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(admin.clone()).address();
+
+    let mut config = config(env, &token);
+    customize(&mut config);
+    let contract_id = env.register(CompetitionContract, (admin.clone(), config, None::<Address>, 0u32));
+
+    (CompetitionContractClient::new(env, &contract_id), token, admin)
+}
+
+fn create_competition<'a>(
+    env: &Env,
+    allowlist_root: Option<BytesN<32>>,
+    allowlist_leaf: AllowlistLeaf,
+) -> (CompetitionContractClient<'a>, Address) {
+    let (client, token, _) = deploy(env, |config| {
+        config.allowlist_root = allowlist_root;
+        config.allowlist_leaf = allowlist_leaf;
+    });
+    (client, token)
+}
+
+// Fábrica mínima: registra os eventos de ciclo de vida reportados pela competição e
+// simula a pausa global.
+#[contract]
+struct MockFactory;
+
+#[contractimpl]
+impl MockFactory {
+    pub fn __constructor(env: Env, token: Address) {
+        env.storage().instance().set(&symbol_short!("token"), &token);
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().get(&symbol_short!("paused")).unwrap_or(false)
+    }
+
+    pub fn set_paused(env: Env, paused: bool) {
+        env.storage().instance().set(&symbol_short!("paused"), &paused);
+    }
+
+    pub fn report_lifecycle(env: Env, competition: Address, event: LifecycleEvent) {
+        competition.require_auth();
+        let mut events: Vec<LifecycleEvent> =
+            env.storage().instance().get(&symbol_short!("events")).unwrap_or_else(|| Vec::new(&env));
+        events.push_back(event);
+        env.storage().instance().set(&symbol_short!("events"), &events);
+    }
+
+    // Como a fábrica real, puxa a taxa da competição
+    pub fn deposit_fee(env: Env, competition: Address, amount: i128) {
+        competition.require_auth();
+        let token: Address = env.storage().instance().get(&symbol_short!("token")).unwrap();
+        TokenClient::new(&env, &token).transfer(&competition, env.current_contract_address(), &amount);
+    }
+
+    pub fn last_event(env: Env) -> LifecycleEvent {
+        let events: Vec<LifecycleEvent> = env.storage().instance().get(&symbol_short!("events")).unwrap();
+        events.last().unwrap()
+    }
+}
+
+// Como `deploy`, mas implantada pela `MockFactory`, com a taxa da plataforma informada.
+fn deploy_with_factory<'a>(
+    env: &Env,
+    platform_fee_bps: u32,
+    customize: impl FnOnce(&mut CompetitionConfig),
+) -> (CompetitionContractClient<'a>, Address, MockFactoryClient<'a>) {
+    let admin = Address::generate(env);
+    let token = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let factory = env.register(MockFactory, (token.clone(),));
+
+    let mut config = config(env, &token);
+    config.treasury = None;
+    customize(&mut config);
+    let contract_id =
+        env.register(CompetitionContract, (admin, config, Some(factory.clone()), platform_fee_bps));
+
+    (CompetitionContractClient::new(env, &contract_id), token, MockFactoryClient::new(env, &factory))
+}
+
+fn funded_player(env: &Env, token: &Address) -> Address {
+    let player = Address::generate(env);
+    StellarAssetClient::new(env, token).mint(&player, &ENTRY_FEE);
+    player
+}
+
+fn token_balance(env: &Env, token: &Address, holder: &Address) -> i128 {
+    TokenClient::new(env, token).balance(holder)
+}
+
+fn hash_pair(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (first, second) = if a.to_array() <= b.to_array() { (a, b) } else { (b, a) };
+    let mut data = Bytes::new(env);
+    data.append(&first.clone().into());
+    data.append(&second.clone().into());
+    env.crypto().sha256(&data).into()
+}
+
+fn address_leaf(env: &Env, address: &Address) -> BytesN<32> {
+    env.crypto().sha256(&address.clone().to_xdr(env)).into()
+}
+
+fn username_leaf(env: &Env, username: &Symbol) -> BytesN<32> {
+    env.crypto().sha256(&username.clone().to_xdr(env)).into()
+}
+
+// Níveis da árvore, das folhas até a raiz. Um nó sem par sobe sem hash para o nível seguinte.
+fn build_tree(env: &Env, leaves: std::vec::Vec<BytesN<32>>) -> std::vec::Vec<std::vec::Vec<BytesN<32>>> {
+    let mut levels = std::vec![leaves];
+    while levels.last().unwrap().len() > 1 {
+        let next = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => hash_pair(env, a, b),
+                [a] => a.clone(),
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+fn root(levels: &[std::vec::Vec<BytesN<32>>]) -> BytesN<32> {
+    levels.last().unwrap()[0].clone()
+}
+
+fn proof(env: &Env, levels: &[std::vec::Vec<BytesN<32>>], mut index: usize) -> Vec<BytesN<32>> {
+    let mut proof = Vec::new(env);
+    for level in &levels[..levels.len() - 1] {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push_back(sibling.clone());
+        }
+        index /= 2;
+    }
+    proof
+}
+
+fn username(env: &Env, index: usize) -> Symbol {
+    Symbol::new(env, &std::format!("player{index}"))
+}
+
+#[test]
+fn open_competition_accepts_empty_proof() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token) = create_competition(&env, None, AllowlistLeaf::Address);
+
+    let player = funded_player(&env, &token);
+    client.join(&player, &username(&env, 0), &Vec::new(&env));
+
+    assert_eq!(client.get_escrow_total(), ENTRY_FEE);
+}
+
+#[test]
+fn single_entry_allowlist_uses_leaf_as_root() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token) = create_competition(&env, None, AllowlistLeaf::Address);
+    let player = funded_player(&env, &token);
+
+    let levels = build_tree(&env, std::vec![address_leaf(&env, &player)]);
+    client.set_allowlist_root(&Some(root(&levels)));
+
+    let proof = proof(&env, &levels, 0);
+    assert!(proof.is_empty());
+    client.join(&player, &username(&env, 0), &proof);
+}
+
+#[test]
+fn joins_with_proofs_of_various_depths() {
+    // 2, 4, 8 e 16 folhas dão profundidades 1 a 4; 5 e 11 exercitam nós sem par
+    for size in [2usize, 4, 5, 8, 11, 16] {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, token) = create_competition(&env, None, AllowlistLeaf::Address);
+
+        let players: std::vec::Vec<Address> = (0..size).map(|_| funded_player(&env, &token)).collect();
+        let levels = build_tree(&env, players.iter().map(|player| address_leaf(&env, player)).collect());
+        client.set_allowlist_root(&Some(root(&levels)));
+
+        for (index, player) in players.iter().enumerate() {
+            let proof = proof(&env, &levels, index);
+            assert!((proof.len() as usize) < levels.len());
+            client.join(player, &username(&env, index), &proof);
+        }
+
+        assert_eq!(client.get_escrow_total(), ENTRY_FEE * size as i128);
+    }
+}
+
+#[test]
+fn rejects_address_outside_allowlist() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token) = create_competition(&env, None, AllowlistLeaf::Address);
+
+    let players: std::vec::Vec<Address> = (0..4).map(|_| funded_player(&env, &token)).collect();
+    let levels = build_tree(&env, players.iter().map(|player| address_leaf(&env, player)).collect());
+    client.set_allowlist_root(&Some(root(&levels)));
+
+    // Prova válida de outro jogador não serve para quem está fora da lista
+    let outsider = funded_player(&env, &token);
+    let result = client.try_join(&outsider, &username(&env, 9), &proof(&env, &levels, 0));
+    assert!(result.is_err());
+
+    let result = client.try_join(&outsider, &username(&env, 9), &Vec::new(&env));
+    assert!(result.is_err());
+}
+
+#[test]
+fn rejects_tampered_or_truncated_proof() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token) = create_competition(&env, None, AllowlistLeaf::Address);
+
+    let players: std::vec::Vec<Address> = (0..8).map(|_| funded_player(&env, &token)).collect();
+    let levels = build_tree(&env, players.iter().map(|player| address_leaf(&env, player)).collect());
+    client.set_allowlist_root(&Some(root(&levels)));
+
+    let valid = proof(&env, &levels, 3);
+
+    let mut tampered = valid.clone();
+    tampered.set(1, BytesN::from_array(&env, &[7; 32]));
+    assert!(client.try_join(&players[3], &username(&env, 3), &tampered).is_err());
+
+    let mut truncated = valid.clone();
+    truncated.pop_back();
+    assert!(client.try_join(&players[3], &username(&env, 3), &truncated).is_err());
+
+    // Prova de outra folha da mesma árvore
+    assert!(client.try_join(&players[3], &username(&env, 3), &proof(&env, &levels, 4)).is_err());
+
+    client.join(&players[3], &username(&env, 3), &valid);
+}
+
+#[test]
+fn username_allowlist_checks_the_chosen_username() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let usernames: std::vec::Vec<Symbol> = (0..3).map(|index| username(&env, index)).collect();
+    let levels = build_tree(&env, usernames.iter().map(|name| username_leaf(&env, name)).collect());
+    let (client, token) = create_competition(&env, Some(root(&levels)), AllowlistLeaf::Username);
+
+    let player = funded_player(&env, &token);
+    let proof = proof(&env, &levels, 2);
+    assert!(client.try_join(&player, &username(&env, 7), &proof).is_err());
+
+    client.join(&player, &usernames[2], &proof);
+}
+
+#[test]
+fn admin_rotates_root_before_registration_closes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token) = create_competition(&env, None, AllowlistLeaf::Address);
+
+    let first: std::vec::Vec<Address> = (0..2).map(|_| funded_player(&env, &token)).collect();
+    let first_levels = build_tree(&env, first.iter().map(|player| address_leaf(&env, player)).collect());
+    client.set_allowlist_root(&Some(root(&first_levels)));
+    client.join(&first[0], &username(&env, 0), &proof(&env, &first_levels, 0));
+
+    let second: std::vec::Vec<Address> = (0..3).map(|_| funded_player(&env, &token)).collect();
+    let second_levels = build_tree(&env, second.iter().map(|player| address_leaf(&env, player)).collect());
+    client.set_allowlist_root(&Some(root(&second_levels)));
+    assert_eq!(client.get_allowlist_root(), Some(root(&second_levels)));
+
+    // Provas da raiz antiga deixam de valer; quem já entrou continua inscrito
+    assert!(client.try_join(&first[1], &username(&env, 1), &proof(&env, &first_levels, 1)).is_err());
+    client.join(&second[2], &username(&env, 2), &proof(&env, &second_levels, 2));
+    assert_eq!(client.get_fee_paid(&username(&env, 0)), Some(ENTRY_FEE));
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE + 1);
+    assert!(client.try_set_allowlist_root(&Some(root(&first_levels))).is_err());
+    assert!(client.try_set_allowlist_root(&None).is_err());
+}
+
+#[test]
+fn clearing_root_opens_the_competition() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token) = create_competition(&env, Some(BytesN::from_array(&env, &[1; 32])), AllowlistLeaf::Address);
+
+    let player = funded_player(&env, &token);
+    assert!(client.try_join(&player, &username(&env, 0), &Vec::new(&env)).is_err());
+
+    client.set_allowlist_root(&None);
+    client.join(&player, &username(&env, 0), &vec![&env]);
+}
+
+#[test]
+fn settlement_is_rejected_once_the_competition_is_refundable() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| config.min_participants = 2);
+
+    let player = funded_player(&env, &token);
+    client.join(&player, &username(&env, 0), &Vec::new(&env));
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE + 1);
+    assert!(client.is_refundable());
+    assert!(client.try_distribute_prizes(&vec![&env, username(&env, 0)]).is_err());
+
+    client.claim_refund(&player);
+    assert_eq!(token_balance(&env, &token, &player), ENTRY_FEE);
+}
+
+#[test]
+fn admin_transfer_moves_roles_still_held_by_the_previous_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, admin) = deploy(&env, |_| {});
+
+    let treasury = Address::generate(&env);
+    client.set_treasury(&treasury);
+
+    let new_admin = Address::generate(&env);
+    client.propose_admin(&new_admin);
+    client.accept_admin();
+
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(client.get_oracle(), new_admin);
+    assert_eq!(client.get_moderator(), new_admin);
+    // Papéis já delegados a outro endereço não mudam
+    assert_eq!(client.get_treasury(), treasury);
+    assert_ne!(client.get_oracle(), admin);
+}
+
+#[test]
+#[should_panic(expected = "Competition is upgraded by its factory")]
+fn factory_deployed_competition_rejects_admin_upgrade() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let factory = Address::generate(&env);
+    let mut config = config(&env, &token);
+    config.treasury = None;
+    let contract_id = env.register(CompetitionContract, (admin, config, Some(factory), 0u32));
+    let client = CompetitionContractClient::new(&env, &contract_id);
+
+    client.upgrade(&BytesN::from_array(&env, &[0; 32]));
+}
+
+#[test]
+fn individual_refund_claims_do_not_close_the_competition() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, factory) = deploy_with_factory(&env, 0, |config| config.min_participants = 3);
+
+    let first = funded_player(&env, &token);
+    let second = funded_player(&env, &token);
+    client.join(&first, &username(&env, 0), &Vec::new(&env));
+    client.join(&second, &username(&env, 1), &Vec::new(&env));
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE + 1);
+    client.claim_refund(&first);
+    assert!(matches!(factory.last_event(), LifecycleEvent::Joined(1)));
+    assert_eq!(client.get_escrow_total(), ENTRY_FEE);
+
+    client.claim_refund(&second);
+    assert!(matches!(factory.last_event(), LifecycleEvent::Refunded));
+    assert_eq!(client.get_escrow_total(), 0);
+}
+
+#[test]
+fn platform_fee_is_pulled_by_the_factory() {
+    let env = Env::default();
+    env.mock_all_auths();
+    // 10% de taxa da plataforma
+    let (client, token, factory) = deploy_with_factory(&env, 1000, |_| {});
+
+    let players: std::vec::Vec<Address> = (0..2).map(|_| funded_player(&env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(&env, index), &Vec::new(&env));
+    }
+
+    // Só o oráculo assina: a transferência para a fábrica depende da pré-autorização da competição
+    let leaderboard = vec![&env, username(&env, 1), username(&env, 0)];
+    client
+        .mock_auths(&[MockAuth {
+            address: &client.get_oracle(),
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "distribute_prizes",
+                args: (leaderboard.clone(),).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .distribute_prizes(&leaderboard);
+
+    assert_eq!(token_balance(&env, &token, &factory.address), 20);
+    assert_eq!(token_balance(&env, &token, &players[1]), 180);
+    assert_eq!(client.get_escrow_total(), 0);
+    assert!(matches!(factory.last_event(), LifecycleEvent::Settled(200)));
+}
+
+#[test]
+fn withdrawal_promotes_the_first_waitlisted_entry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| config.max_participants = 2);
+
+    let players: std::vec::Vec<Address> = (0..4).map(|_| funded_player(&env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(&env, index), &Vec::new(&env));
+    }
+    let waitlist = client.get_waitlist();
+    assert_eq!(waitlist.len(), 2);
+    assert_eq!(waitlist.get(0).unwrap().username, username(&env, 2));
+    // A taxa de quem está na lista de espera também fica em custódia
+    assert_eq!(client.get_escrow_total(), 4 * ENTRY_FEE);
+
+    client.withdraw(&players[0]);
+    assert_eq!(token_balance(&env, &token, &players[0]), ENTRY_FEE);
+
+    let waitlist = client.get_waitlist();
+    assert_eq!(waitlist.len(), 1);
+    assert_eq!(waitlist.get(0).unwrap().username, username(&env, 3));
+    assert_eq!(client.get_escrow_total(), 3 * ENTRY_FEE);
+
+    // O promovido disputa: vence e leva o pool dos dois inscritos
+    client.distribute_prizes(&vec![&env, username(&env, 2)]);
+    assert_eq!(token_balance(&env, &token, &players[2]), 2 * ENTRY_FEE);
+    assert_eq!(token_balance(&env, &token, &players[3]), ENTRY_FEE);
+}
+
+#[test]
+fn disqualification_promotes_the_first_waitlisted_entry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, admin) = deploy(&env, |config| config.max_participants = 2);
+
+    let players: std::vec::Vec<Address> = (0..4).map(|_| funded_player(&env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(&env, index), &Vec::new(&env));
+    }
+
+    client.disqualify(&admin, &username(&env, 0), &SdkString::from_str(&env, "smurf"));
+    let waitlist = client.get_waitlist();
+    assert_eq!(waitlist.len(), 1);
+    assert_eq!(waitlist.get(0).unwrap().username, username(&env, 3));
+
+    // Depois do fim das inscrições a vaga não é preenchida
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE + 1);
+    client.disqualify(&admin, &username(&env, 1), &SdkString::from_str(&env, "smurf"));
+    assert_eq!(client.get_waitlist().len(), 1);
+
+    client.distribute_prizes(&vec![&env, username(&env, 2)]);
+    assert_eq!(token_balance(&env, &token, &players[2]), ENTRY_FEE);
+    assert_eq!(token_balance(&env, &token, &players[3]), ENTRY_FEE);
+    assert_escrow_matches_balance(&env, &client, &token);
+}
+
+#[test]
+fn waitlist_is_refunded_on_withdrawal_and_settlement() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| config.max_participants = 1);
+
+    let players: std::vec::Vec<Address> = (0..3).map(|_| funded_player(&env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(&env, index), &Vec::new(&env));
+    }
+
+    // Quem sai da lista de espera recebe a taxa de volta sem afetar os inscritos
+    client.withdraw(&players[1]);
+    assert_eq!(token_balance(&env, &token, &players[1]), ENTRY_FEE);
+    assert_eq!(client.get_waitlist().len(), 1);
+
+    // Depois do prazo a vaga não é mais preenchida pela lista de espera
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE + 1);
+    client.distribute_prizes(&vec![&env, username(&env, 0)]);
+    assert_eq!(token_balance(&env, &token, &players[0]), ENTRY_FEE);
+    assert_eq!(token_balance(&env, &token, &players[2]), ENTRY_FEE);
+    assert!(client.get_waitlist().is_empty());
+    assert_eq!(client.get_escrow_total(), 0);
+}
+
+#[test]
+fn cancel_refunds_participants_and_waitlist() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| config.max_participants = 1);
+
+    let players: std::vec::Vec<Address> = (0..2).map(|_| funded_player(&env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(&env, index), &Vec::new(&env));
+    }
+
+    client.cancel();
+    for player in players.iter() {
+        assert_eq!(token_balance(&env, &token, player), ENTRY_FEE);
+    }
+    assert_eq!(client.get_escrow_total(), 0);
+}
+
+#[test]
+fn competition_pause_blocks_joins_and_settlement_but_not_withdrawals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |_| {});
+
+    let players: std::vec::Vec<Address> = (0..3).map(|_| funded_player(&env, &token)).collect();
+    client.join(&players[0], &username(&env, 0), &Vec::new(&env));
+    client.join(&players[1], &username(&env, 1), &Vec::new(&env));
+
+    client.pause();
+    assert!(client.is_paused());
+    assert!(client.try_join(&players[2], &username(&env, 2), &Vec::new(&env)).is_err());
+    assert!(client.try_distribute_prizes(&vec![&env, username(&env, 0)]).is_err());
+
+    // Os fundos continuam podendo sair
+    client.withdraw(&players[1]);
+    assert_eq!(token_balance(&env, &token, &players[1]), ENTRY_FEE);
+
+    client.unpause();
+    client.join(&players[2], &username(&env, 2), &Vec::new(&env));
+    client.distribute_prizes(&vec![&env, username(&env, 0)]);
+    assert_eq!(token_balance(&env, &token, &players[0]), 2 * ENTRY_FEE);
+}
+
+#[test]
+fn paused_competition_still_refunds_players() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| config.min_participants = 3);
+
+    let players: std::vec::Vec<Address> = (0..2).map(|_| funded_player(&env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(&env, index), &Vec::new(&env));
+    }
+    client.pause();
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE + 1);
+    client.claim_refund(&players[0]);
+    client.refund_all();
+    for player in players.iter() {
+        assert_eq!(token_balance(&env, &token, player), ENTRY_FEE);
+    }
+    assert_eq!(client.get_escrow_total(), 0);
+}
+
+#[test]
+fn factory_pause_blocks_joins_and_settlement_but_not_cancellation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, factory) = deploy_with_factory(&env, 0, |_| {});
+
+    let players: std::vec::Vec<Address> = (0..2).map(|_| funded_player(&env, &token)).collect();
+    client.join(&players[0], &username(&env, 0), &Vec::new(&env));
+
+    // A pausa global vale mesmo sem a competição estar pausada
+    factory.set_paused(&true);
+    assert!(client.is_paused());
+    assert!(client.try_join(&players[1], &username(&env, 1), &Vec::new(&env)).is_err());
+    assert!(client.try_distribute_prizes(&vec![&env, username(&env, 0)]).is_err());
+
+    factory.set_paused(&false);
+    client.join(&players[1], &username(&env, 1), &Vec::new(&env));
+
+    factory.set_paused(&true);
+    client.withdraw(&players[1]);
+    client.cancel();
+    for player in players.iter() {
+        assert_eq!(token_balance(&env, &token, player), ENTRY_FEE);
+    }
+    assert!(matches!(factory.last_event(), LifecycleEvent::Cancelled));
+}
+
+// A custódia registrada deve sempre bater com o saldo de tokens do contrato.
+fn assert_escrow_matches_balance(env: &Env, client: &CompetitionContractClient, token: &Address) {
+    assert_eq!(client.get_escrow_total(), token_balance(env, token, &client.address));
+}
+
+#[test]
+fn escrow_tracks_withdrawals_and_disqualifications() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| {
+        config.disqualification_policy = DisqualificationPolicy::Forfeit;
+    });
+
+    let players: std::vec::Vec<Address> = (0..4).map(|_| funded_player(&env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(&env, index), &Vec::new(&env));
+    }
+    assert_eq!(client.get_escrow_total(), 4 * ENTRY_FEE);
+    assert_escrow_matches_balance(&env, &client, &token);
+
+    client.withdraw(&players[0]);
+    assert_eq!(client.get_escrow_total(), 3 * ENTRY_FEE);
+    assert_escrow_matches_balance(&env, &client, &token);
+
+    // Com a política `Forfeit`, a taxa do desclassificado continua em custódia
+    let reason = SdkString::from_str(&env, "cheating");
+    client.disqualify(&client.get_admin(), &username(&env, 1), &reason);
+    assert_eq!(client.get_escrow_total(), 3 * ENTRY_FEE);
+    assert_eq!(token_balance(&env, &token, &players[1]), 0);
+    assert_escrow_matches_balance(&env, &client, &token);
+
+    // e entra no pool dividido na liquidação
+    client.distribute_prizes(&vec![&env, username(&env, 1), username(&env, 2)]);
+    assert_eq!(token_balance(&env, &token, &players[2]), 3 * ENTRY_FEE);
+    assert_eq!(client.get_escrow_total(), 0);
+    assert_escrow_matches_balance(&env, &client, &token);
+}
+
+#[test]
+fn forfeited_disqualification_fees_return_to_the_player_on_cancel() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, admin) = deploy(&env, |config| {
+        config.disqualification_policy = DisqualificationPolicy::Forfeit;
+    });
+
+    let players: std::vec::Vec<Address> = (0..2).map(|_| funded_player(&env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(&env, index), &Vec::new(&env));
+    }
+    client.disqualify(&admin, &username(&env, 0), &SdkString::from_str(&env, "cheating"));
+    assert_eq!(client.get_disqualification(&username(&env, 0)).unwrap().forfeited_fee, ENTRY_FEE);
+
+    // Sem liquidação não há pool: a taxa retida volta ao desclassificado, não ao admin
+    client.cancel();
+    for player in players.iter() {
+        assert_eq!(token_balance(&env, &token, player), ENTRY_FEE);
+    }
+    assert_eq!(token_balance(&env, &token, &admin), 0);
+    assert_eq!(token_balance(&env, &token, &client.get_treasury()), 0);
+    let record = client.get_disqualification(&username(&env, 0)).unwrap();
+    assert!(record.refunded);
+    assert_eq!(record.forfeited_fee, 0);
+    assert_eq!(client.get_escrow_total(), 0);
+}
+
+const CHECK_IN_END: u64 = DEADLINE + 100;
+
+// Inscreve `count` jogadores numa competição com check-in e a política de ausência informada.
+fn deploy_with_check_in<'a>(
+    env: &Env,
+    count: usize,
+    customize: impl FnOnce(&mut CompetitionConfig),
+) -> (CompetitionContractClient<'a>, Address, std::vec::Vec<Address>) {
+    let (client, token, _) = deploy(env, |config| {
+        config.check_in_end = CHECK_IN_END;
+        customize(config);
+    });
+    let players: std::vec::Vec<Address> = (0..count).map(|_| funded_player(env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(env, index), &Vec::new(env));
+    }
+    (client, token, players)
+}
+
+#[test]
+fn check_in_is_only_open_between_the_deadline_and_check_in_end() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, players) = deploy_with_check_in(&env, 2, |_| {});
+
+    // Enquanto as inscrições estão abertas, ainda não há check-in
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE);
+    assert!(client.try_check_in(&players[0]).is_err());
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE + 1);
+    client.check_in(&players[0]);
+    assert!(client.try_check_in(&players[0]).is_err());
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = CHECK_IN_END);
+    client.check_in(&players[1]);
+    assert_eq!(client.get_checked_in(), vec![&env, username(&env, 0), username(&env, 1)]);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = CHECK_IN_END + 1);
+    assert!(client.try_check_in(&players[1]).is_err());
+}
+
+#[test]
+fn settlement_waits_for_the_check_in_window_to_close() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, players) = deploy_with_check_in(&env, 2, |_| {});
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE + 1);
+    client.check_in(&players[0]);
+    client.check_in(&players[1]);
+    assert!(client.try_distribute_prizes(&vec![&env, username(&env, 0)]).is_err());
+    assert!(client.try_finalize_check_in().is_err());
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = CHECK_IN_END + 1);
+    client.distribute_prizes(&vec![&env, username(&env, 0)]);
+    assert_eq!(client.get_escrow_total(), 0);
+}
+
+#[test]
+fn no_shows_are_refunded_under_the_refund_policy() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, players) = deploy_with_check_in(&env, 3, |config| {
+        config.no_show_policy = NoShowPolicy::Refund;
+    });
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE + 1);
+    client.check_in(&players[0]);
+    client.check_in(&players[1]);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = CHECK_IN_END + 1);
+    client.finalize_check_in();
+    assert_eq!(token_balance(&env, &token, &players[2]), ENTRY_FEE);
+    assert_eq!(client.get_fee_paid(&username(&env, 2)), None);
+    assert!(client.get_no_show(&username(&env, 2)).is_none());
+    assert_eq!(client.get_escrow_total(), 2 * ENTRY_FEE);
+    assert_escrow_matches_balance(&env, &client, &token);
+
+    // Só quem fez check-in disputa o pool
+    client.distribute_prizes(&vec![&env, username(&env, 0)]);
+    assert_eq!(token_balance(&env, &token, &players[0]), 2 * ENTRY_FEE);
+}
+
+#[test]
+fn forfeited_no_show_fees_join_the_prize_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, players) = deploy_with_check_in(&env, 3, |config| {
+        config.no_show_policy = NoShowPolicy::Forfeit;
+    });
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE + 1);
+    client.check_in(&players[0]);
+    client.check_in(&players[1]);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = CHECK_IN_END + 1);
+    client.finalize_check_in();
+    assert_eq!(token_balance(&env, &token, &players[2]), 0);
+    assert_eq!(client.get_no_show(&username(&env, 2)).unwrap().forfeited_fee, ENTRY_FEE);
+    assert_eq!(client.get_escrow_total(), 3 * ENTRY_FEE);
+
+    client.distribute_prizes(&vec![&env, username(&env, 0)]);
+    assert_eq!(token_balance(&env, &token, &players[0]), 3 * ENTRY_FEE);
+    assert_escrow_matches_balance(&env, &client, &token);
+}
+
+#[test]
+fn forfeited_no_show_fees_return_to_the_player_on_cancel() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, players) = deploy_with_check_in(&env, 2, |config| {
+        config.no_show_policy = NoShowPolicy::Forfeit;
+    });
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE + 1);
+    client.check_in(&players[0]);
+    env.ledger().with_mut(|ledger| ledger.timestamp = CHECK_IN_END + 1);
+    client.finalize_check_in();
+
+    // Sem liquidação, a taxa retida volta ao ausente, não à tesouraria
+    client.cancel();
+    for player in players.iter() {
+        assert_eq!(token_balance(&env, &token, player), ENTRY_FEE);
+    }
+    assert_eq!(token_balance(&env, &token, &client.get_treasury()), 0);
+    assert!(client.get_no_show(&username(&env, 1)).is_none());
+    assert_eq!(client.get_escrow_total(), 0);
+}
+
+#[test]
+fn min_participants_counts_only_checked_in_players() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, players) = deploy_with_check_in(&env, 3, |config| {
+        config.min_participants = 2;
+    });
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE + 1);
+    client.check_in(&players[0]);
+
+    // O campo só é avaliado quando a janela de check-in fecha
+    assert!(!client.is_refundable());
+    env.ledger().with_mut(|ledger| ledger.timestamp = CHECK_IN_END + 1);
+    assert!(client.is_refundable());
+    assert!(client.try_finalize_check_in().is_err());
+
+    client.refund_all();
+    for player in players.iter() {
+        assert_eq!(token_balance(&env, &token, player), ENTRY_FEE);
+    }
+    assert_eq!(client.get_escrow_total(), 0);
+}
+
+#[test]
+fn escrow_is_emptied_by_disqualification_refunds_and_refund_all() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| config.min_participants = 4);
+
+    let players: std::vec::Vec<Address> = (0..3).map(|_| funded_player(&env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(&env, index), &Vec::new(&env));
+    }
+
+    client.disqualify(&client.get_admin(), &username(&env, 0), &SdkString::from_str(&env, "smurf"));
+    assert_eq!(token_balance(&env, &token, &players[0]), ENTRY_FEE);
+    assert_eq!(client.get_escrow_total(), 2 * ENTRY_FEE);
+    assert_escrow_matches_balance(&env, &client, &token);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE + 1);
+    client.refund_all();
+    for player in players.iter() {
+        assert_eq!(token_balance(&env, &token, player), ENTRY_FEE);
+    }
+    assert_eq!(client.get_escrow_total(), 0);
+    assert_escrow_matches_balance(&env, &client, &token);
+}
+
+#[test]
+fn escrow_records_the_fee_each_entrant_paid() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| {
+        config.fee_schedule = vec![&env, FeeStep { until: DEADLINE / 2, fee: ENTRY_FEE / 2 }];
+    });
+
+    let early = funded_player(&env, &token);
+    client.join(&early, &username(&env, 0), &Vec::new(&env));
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = DEADLINE / 2 + 1);
+    let late = funded_player(&env, &token);
+    client.join(&late, &username(&env, 1), &Vec::new(&env));
+
+    assert_eq!(client.get_fee_paid(&username(&env, 0)), Some(ENTRY_FEE / 2));
+    assert_eq!(client.get_fee_paid(&username(&env, 1)), Some(ENTRY_FEE));
+    assert_eq!(client.get_escrow_total(), ENTRY_FEE / 2 + ENTRY_FEE);
+
+    // O reembolso devolve o que cada um pagou, não a taxa vigente
+    client.withdraw(&early);
+    assert_eq!(token_balance(&env, &token, &early), ENTRY_FEE);
+    assert_eq!(client.get_escrow_total(), ENTRY_FEE);
+    assert_escrow_matches_balance(&env, &client, &token);
+}
+
+fn assert_valid_payout_table(payout_rules: &Vec<u32>, places: u32) {
+    assert_eq!(payout_rules.len(), places);
+    assert_eq!(payout_rules.iter().sum::<u32>(), 10000);
+    for i in 1..payout_rules.len() {
+        assert!(payout_rules.get(i).unwrap() <= payout_rules.get(i - 1).unwrap());
+    }
+}
+
+#[test]
+fn payout_curves_always_sum_to_10000() {
+    let env = Env::default();
+    env.cost_estimate().budget().reset_unlimited();
+
+    for places in 1..=100 {
+        let top_n = expand_payout_curve(&env, &PayoutCurve::TopNEqual(places));
+        assert_valid_payout_table(&top_n, places);
+        // Na divisão igual a diferença entre posições é no máximo um ponto-base
+        assert!(top_n.get(0).unwrap() - top_n.get(places - 1).unwrap() <= 1);
+
+        assert_valid_payout_table(&expand_payout_curve(&env, &PayoutCurve::Linear(places)), places);
+
+        for ratio_bps in [1, 2500, 5000, 6667, 9999, 10000] {
+            let geometric = expand_payout_curve(&env, &PayoutCurve::Geometric(places, ratio_bps));
+            assert_valid_payout_table(&geometric, places);
+        }
+    }
+
+    assert_eq!(expand_payout_curve(&env, &PayoutCurve::WinnerTakesAll), vec![&env, 10000]);
+    assert_eq!(expand_payout_curve(&env, &PayoutCurve::Linear(4)), vec![&env, 4000, 3000, 2000, 1000]);
+    assert_eq!(
+        expand_payout_curve(&env, &PayoutCurve::Geometric(3, 5000)),
+        vec![&env, 5715, 2857, 1428]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Payout curve must pay between 1 and 100 places")]
+fn payout_curve_rejects_too_many_places() {
+    let env = Env::default();
+    expand_payout_curve(&env, &PayoutCurve::Linear(101));
+}
+
+#[test]
+fn payout_table_follows_the_bracket_for_the_field_size() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| {
+        config.payout_brackets =
+            vec![&env, PayoutBracket { min_participants: 3, payout_rules: PayoutCurve::TopNEqual(3) }];
+    });
+
+    let players: std::vec::Vec<Address> = (0..3).map(|_| funded_player(&env, &token)).collect();
+    client.join(&players[0], &username(&env, 0), &Vec::new(&env));
+    client.join(&players[1], &username(&env, 1), &Vec::new(&env));
+    assert_eq!(client.get_payout_table(), vec![&env, 10000]);
+
+    client.join(&players[2], &username(&env, 2), &Vec::new(&env));
+    assert_eq!(client.get_payout_table(), vec![&env, 3334, 3333, 3333]);
+
+    // 300 dividido em 3334/3333/3333 pontos-base: a sobra do arredondamento vai para a tesouraria
+    client.distribute_prizes(&vec![&env, username(&env, 0), username(&env, 1), username(&env, 2)]);
+    assert_eq!(token_balance(&env, &token, &players[0]), 100);
+    assert_eq!(token_balance(&env, &token, &players[1]), 99);
+    assert_eq!(token_balance(&env, &token, &players[2]), 99);
+    assert_eq!(client.get_settlement_receipt().unwrap().remainder, 2);
+}
+
+#[test]
+fn unpaid_fixed_prizes_return_to_sponsors_pro_rata() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| {
+        config.fixed_prizes = vec![&env, 150, 50];
+    });
+
+    let player = funded_player(&env, &token);
+    assert!(client.try_join(&player, &username(&env, 0), &Vec::new(&env)).is_err());
+
+    let sponsors: std::vec::Vec<Address> = [150, 50]
+        .iter()
+        .map(|amount| {
+            let sponsor = Address::generate(&env);
+            StellarAssetClient::new(&env, &token).mint(&sponsor, amount);
+            client.fund_prizes(&sponsor, amount);
+            sponsor
+        })
+        .collect();
+    assert_eq!(client.get_sponsored_total(), 200);
+
+    client.join(&player, &username(&env, 0), &Vec::new(&env));
+    client.distribute_prizes(&vec![&env, username(&env, 0)]);
+
+    // O único jogador leva o primeiro prêmio fixo e todo o excedente (a própria taxa)
+    assert_eq!(token_balance(&env, &token, &player), 250);
+    // O segundo prêmio (50) volta 3:1; a poeira fica com o maior patrocinador
+    assert_eq!(token_balance(&env, &token, &sponsors[0]), 38);
+    assert_eq!(token_balance(&env, &token, &sponsors[1]), 12);
+
+    let receipt = client.get_settlement_receipt().unwrap();
+    assert_eq!(receipt.paid_out, 250);
+    assert_eq!(receipt.sponsor_refund, 50);
+    assert_eq!(receipt.remainder, 0);
+    assert_eq!(client.get_escrow_total(), 0);
+}
+
+#[test]
+fn sponsors_are_refunded_when_the_competition_is_cancelled() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| config.fixed_prizes = vec![&env, 100]);
+
+    let sponsor = Address::generate(&env);
+    StellarAssetClient::new(&env, &token).mint(&sponsor, &120);
+    client.fund_prizes(&sponsor, &120);
+    let player = funded_player(&env, &token);
+    client.join(&player, &username(&env, 0), &Vec::new(&env));
+
+    client.cancel();
+    assert_eq!(token_balance(&env, &token, &sponsor), 120);
+    assert_eq!(token_balance(&env, &token, &player), ENTRY_FEE);
+    assert_eq!(client.get_escrow_total(), 0);
+}
+
+#[test]
+fn remainder_rolls_over_into_the_next_active_competition() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| {
+        config.payout_rules = PayoutCurve::Table(vec![&env, 5000]);
+        config.remainder_policy = RemainderPolicy::RollOver;
+    });
+    let next_id = env.register(
+        CompetitionContract,
+        (Address::generate(&env), config(&env, &token), None::<Address>, 0u32),
+    );
+    let next = CompetitionContractClient::new(&env, &next_id);
+    client.set_next_competition(&next_id);
+
+    let players: std::vec::Vec<Address> = (0..2).map(|_| funded_player(&env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(&env, index), &Vec::new(&env));
+    }
+    client.distribute_prizes(&vec![&env, username(&env, 0)]);
+
+    assert_eq!(token_balance(&env, &token, &players[0]), ENTRY_FEE);
+    assert_eq!(next.get_escrow_total(), ENTRY_FEE);
+    assert_eq!(next.get_rolled_over(), ENTRY_FEE);
+    assert_eq!(next.get_sponsored_total(), 0);
+    assert_eq!(client.get_settlement_receipt().unwrap().remainder_recipient, Some(next_id));
+}
+
+#[test]
+fn rolled_over_remainder_goes_to_the_next_treasury_if_it_is_cancelled() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| {
+        config.payout_rules = PayoutCurve::Table(vec![&env, 5000]);
+        config.remainder_policy = RemainderPolicy::RollOver;
+    });
+    let next_id = env.register(
+        CompetitionContract,
+        (Address::generate(&env), config(&env, &token), None::<Address>, 0u32),
+    );
+    let next = CompetitionContractClient::new(&env, &next_id);
+    client.set_next_competition(&next_id);
+
+    let players: std::vec::Vec<Address> = (0..2).map(|_| funded_player(&env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(&env, index), &Vec::new(&env));
+    }
+    client.distribute_prizes(&vec![&env, username(&env, 0)]);
+    let late_player = funded_player(&env, &token);
+    next.join(&late_player, &username(&env, 0), &Vec::new(&env));
+
+    // A competição de origem já foi liquidada e não recebe nada de volta
+    next.cancel();
+    assert_eq!(token_balance(&env, &token, &late_player), ENTRY_FEE);
+    assert_eq!(token_balance(&env, &token, &next.get_treasury()), ENTRY_FEE);
+    assert_eq!(token_balance(&env, &token, &client.address), 0);
+    assert_eq!(next.get_escrow_total(), 0);
+    assert_escrow_matches_balance(&env, &next, &token);
+}
+
+#[test]
+fn roll_over_falls_back_to_treasury_when_the_next_competition_closed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, admin) = deploy(&env, |config| {
+        config.payout_rules = PayoutCurve::Table(vec![&env, 5000]);
+        config.remainder_policy = RemainderPolicy::RollOver;
+    });
+    let next_id = env.register(
+        CompetitionContract,
+        (Address::generate(&env), config(&env, &token), None::<Address>, 0u32),
+    );
+    let next = CompetitionContractClient::new(&env, &next_id);
+    client.set_next_competition(&next_id);
+    next.cancel();
+
+    let players: std::vec::Vec<Address> = (0..2).map(|_| funded_player(&env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(&env, index), &Vec::new(&env));
+    }
+    client.distribute_prizes(&vec![&env, username(&env, 0)]);
+
+    let treasury = client.get_treasury();
+    assert_eq!(token_balance(&env, &token, &treasury), ENTRY_FEE);
+    assert_eq!(token_balance(&env, &token, &admin), 0);
+    assert_eq!(token_balance(&env, &token, &next_id), 0);
+    let receipt = client.get_settlement_receipt().unwrap();
+    assert_eq!(receipt.remainder_policy, RemainderPolicy::RollOver);
+    assert_eq!(receipt.applied_policy, RemainderPolicy::Treasury);
+    assert_eq!(receipt.remainder_recipient, Some(treasury));
+}
+
+// Três jogadores, tabela de 50% e 20%: pool de 300, prêmios de 150 e 60, sobra de 90.
+fn settle_with_remainder_policy(
+    env: &Env,
+    policy: RemainderPolicy,
+    leaderboard: Vec<Symbol>,
+) -> (std::vec::Vec<Address>, CompetitionContractClient<'_>, Address, Address) {
+    let (client, token, admin) = deploy(env, |config| {
+        config.payout_rules = PayoutCurve::Table(vec![env, 5000, 2000]);
+        config.remainder_policy = policy;
+    });
+    let players: std::vec::Vec<Address> = (0..3).map(|_| funded_player(env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(env, index), &Vec::new(env));
+    }
+    client.distribute_prizes(&leaderboard);
+    (players, client, token, admin)
+}
+
+#[test]
+fn pro_rata_remainder_follows_each_winner_prize() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let leaderboard = vec![&env, username(&env, 2), username(&env, 0)];
+    let (players, client, token, _) =
+        settle_with_remainder_policy(&env, RemainderPolicy::ProRataWinners, leaderboard);
+
+    // 90 * 150/210 = 64 e 90 * 60/210 = 25; o ponto que sobra da divisão vai para o primeiro
+    assert_eq!(token_balance(&env, &token, &players[2]), 215);
+    assert_eq!(token_balance(&env, &token, &players[0]), 85);
+    assert_eq!(token_balance(&env, &token, &players[1]), 0);
+
+    let receipt = client.get_settlement_receipt().unwrap();
+    assert_eq!(receipt.paid_out, 210);
+    assert_eq!(receipt.remainder, 90);
+    assert_eq!(receipt.remainder_recipient, None);
+    assert_eq!(client.get_escrow_total(), 0);
+}
+
+#[test]
+fn first_place_remainder_goes_to_the_winner() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let leaderboard = vec![&env, username(&env, 1), username(&env, 2)];
+    let (players, client, token, _) =
+        settle_with_remainder_policy(&env, RemainderPolicy::FirstPlace, leaderboard);
+
+    assert_eq!(token_balance(&env, &token, &players[1]), 240);
+    assert_eq!(token_balance(&env, &token, &players[2]), 60);
+    assert_eq!(client.get_settlement_receipt().unwrap().remainder_recipient, Some(players[1].clone()));
+    assert_eq!(client.get_escrow_total(), 0);
+}
+
+#[test]
+fn winner_policies_fall_back_to_treasury_without_winners() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (players, client, token, admin) =
+        settle_with_remainder_policy(&env, RemainderPolicy::ProRataWinners, Vec::new(&env));
+
+    let treasury = client.get_treasury();
+    assert_eq!(token_balance(&env, &token, &treasury), 300);
+    assert_eq!(token_balance(&env, &token, &admin), 0);
+    for player in players.iter() {
+        assert_eq!(token_balance(&env, &token, player), 0);
+    }
+    let receipt = client.get_settlement_receipt().unwrap();
+    assert_eq!(receipt.remainder, 300);
+    assert_eq!(receipt.applied_policy, RemainderPolicy::Treasury);
+    assert_eq!(receipt.remainder_recipient, Some(treasury));
+}
+
+#[test]
+fn factory_competitions_send_the_treasury_remainder_to_the_factory() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, factory) = deploy_with_factory(&env, 0, |config| {
+        config.payout_rules = PayoutCurve::Table(vec![&env, 5000]);
+    });
+    assert_eq!(client.get_treasury(), factory.address);
+    assert!(client.try_set_treasury(&client.get_admin()).is_err());
+
+    let players: std::vec::Vec<Address> = (0..2).map(|_| funded_player(&env, &token)).collect();
+    for (index, player) in players.iter().enumerate() {
+        client.join(player, &username(&env, index), &Vec::new(&env));
+    }
+    client.distribute_prizes(&vec![&env, username(&env, 0)]);
+
+    assert_eq!(token_balance(&env, &token, &factory.address), ENTRY_FEE);
+    assert_eq!(token_balance(&env, &token, &client.get_admin()), 0);
+    let receipt = client.get_settlement_receipt().unwrap();
+    assert_eq!(receipt.applied_policy, RemainderPolicy::Treasury);
+    assert_eq!(receipt.remainder_recipient, Some(factory.address));
+}
+
+#[test]
+#[should_panic(expected = "Treasury is required without a factory")]
+fn competitions_without_a_factory_require_a_treasury() {
+    let env = Env::default();
+    deploy(&env, |config| config.treasury = None);
+}
+
+fn funded_with(env: &Env, token: &Address, amount: i128) -> Address {
+    let player = Address::generate(env);
+    if amount > 0 {
+        StellarAssetClient::new(env, token).mint(&player, &amount);
+    }
+    player
+}
+
+// Registra um time completo, com cada jogador financiado com o que o modo de taxa cobra dele.
+fn register_full_team(
+    env: &Env,
+    client: &CompetitionContractClient,
+    token: &Address,
+    team_name: &Symbol,
+    fee_mode: TeamFeeMode,
+    member_count: u32,
+    shares: Vec<u32>,
+) -> std::vec::Vec<Address> {
+    let (captain_fee, member_fee) = match fee_mode {
+        TeamFeeMode::CaptainPaysAll => (ENTRY_FEE * (member_count as i128 + 1), 0),
+        TeamFeeMode::PerMember => (ENTRY_FEE, ENTRY_FEE),
+    };
+    let captain = funded_with(env, token, captain_fee);
+    let mut roster = std::vec![captain.clone()];
+    let mut invited = Vec::new(env);
+    for _ in 0..member_count {
+        let member = funded_with(env, token, member_fee);
+        invited.push_back(member.clone());
+        roster.push(member);
+    }
+
+    client.register_team(&captain, team_name, &invited, &shares, &Vec::new(env));
+    for member in invited.iter() {
+        client.accept_invitation(&member, team_name, &Vec::new(env));
+    }
+    roster
+}
+
+#[test]
+fn team_prize_is_split_by_the_captain_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| config.team_size = 2);
+
+    let winners = register_full_team(
+        &env,
+        &client,
+        &token,
+        &symbol_short!("alpha"),
+        TeamFeeMode::CaptainPaysAll,
+        1,
+        vec![&env, 7000, 3000],
+    );
+    register_full_team(
+        &env,
+        &client,
+        &token,
+        &symbol_short!("bravo"),
+        TeamFeeMode::CaptainPaysAll,
+        1,
+        Vec::new(&env),
+    );
+    assert_eq!(client.get_escrow_total(), 4 * ENTRY_FEE);
+
+    client.distribute_prizes(&vec![&env, symbol_short!("alpha"), symbol_short!("bravo")]);
+    assert_eq!(token_balance(&env, &token, &winners[0]), 280);
+    assert_eq!(token_balance(&env, &token, &winners[1]), 120);
+    assert_eq!(client.get_escrow_total(), 0);
+}
+
+#[test]
+fn equal_team_split_gives_rounding_dust_to_the_captain() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| {
+        config.team_size = 3;
+        config.team_fee_mode = TeamFeeMode::PerMember;
+        config.payout_rules = PayoutCurve::Table(vec![&env, 5025]);
+    });
+
+    let winners = register_full_team(
+        &env,
+        &client,
+        &token,
+        &symbol_short!("alpha"),
+        TeamFeeMode::PerMember,
+        2,
+        Vec::new(&env),
+    );
+    register_full_team(
+        &env,
+        &client,
+        &token,
+        &symbol_short!("bravo"),
+        TeamFeeMode::PerMember,
+        2,
+        Vec::new(&env),
+    );
+    let team = client.get_team(&symbol_short!("alpha")).unwrap();
+    assert_eq!(team.contributions, vec![&env, ENTRY_FEE, ENTRY_FEE, ENTRY_FEE]);
+
+    // 50,25% de 600 = 301, dividido em três: 100 para cada e 1 de poeira para o capitão
+    client.distribute_prizes(&vec![&env, symbol_short!("alpha")]);
+    assert_eq!(token_balance(&env, &token, &winners[0]), 101);
+    assert_eq!(token_balance(&env, &token, &winners[1]), 100);
+    assert_eq!(token_balance(&env, &token, &winners[2]), 100);
+}
+
+#[test]
+fn incomplete_teams_are_refunded_at_settlement() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| {
+        config.team_size = 2;
+        config.team_fee_mode = TeamFeeMode::PerMember;
+    });
+
+    register_full_team(
+        &env,
+        &client,
+        &token,
+        &symbol_short!("alpha"),
+        TeamFeeMode::PerMember,
+        1,
+        Vec::new(&env),
+    );
+    let captain = funded_player(&env, &token);
+    let invited = Address::generate(&env);
+    client.register_team(
+        &captain,
+        &symbol_short!("bravo"),
+        &vec![&env, invited],
+        &Vec::new(&env),
+        &Vec::new(&env),
+    );
+    assert_eq!(client.get_escrow_total(), 3 * ENTRY_FEE);
+
+    client.distribute_prizes(&vec![&env, symbol_short!("alpha")]);
+    assert_eq!(token_balance(&env, &token, &captain), ENTRY_FEE);
+    assert!(client.get_team(&symbol_short!("bravo")).is_none());
+    assert_eq!(client.get_escrow_total(), 0);
+}
+
+#[test]
+fn disqualified_team_members_cannot_register_again() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, admin) = deploy(&env, |config| config.team_size = 2);
+
+    let roster = register_full_team(
+        &env,
+        &client,
+        &token,
+        &symbol_short!("alpha"),
+        TeamFeeMode::CaptainPaysAll,
+        1,
+        Vec::new(&env),
+    );
+    client.disqualify(
+        &admin,
+        &symbol_short!("alpha"),
+        &SdkString::from_str(&env, "cheating"),
+    );
+
+    // O depósito do capitão volta para ele
+    assert_eq!(token_balance(&env, &token, &roster[0]), 2 * ENTRY_FEE);
+    let record = client.get_disqualification(&symbol_short!("alpha")).unwrap();
+    assert_eq!(record.members.len(), 2);
+
+    let captain = funded_with(&env, &token, 2 * ENTRY_FEE);
+    let result = client.try_register_team(
+        &captain,
+        &symbol_short!("phoenix"),
+        &vec![&env, roster[1].clone()],
+        &Vec::new(&env),
+        &Vec::new(&env),
+    );
+    assert!(result.is_err());
+
+    // Nem como capitão de um time novo
+    let result = client.try_register_team(
+        &roster[1],
+        &symbol_short!("phoenix"),
+        &vec![&env, captain],
+        &Vec::new(&env),
+        &Vec::new(&env),
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn invited_members_must_be_on_an_address_allowlist() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _) = deploy(&env, |config| {
+        config.team_size = 2;
+        config.team_fee_mode = TeamFeeMode::PerMember;
+    });
+
+    let captain = funded_player(&env, &token);
+    let member = funded_player(&env, &token);
+    let outsider = funded_player(&env, &token);
+    let levels = build_tree(
+        &env,
+        std::vec![address_leaf(&env, &captain), address_leaf(&env, &member)],
+    );
+    client.set_allowlist_root(&Some(root(&levels)));
+
+    let team_name = symbol_short!("alpha");
+    client.register_team(
+        &captain,
+        &team_name,
+        &vec![&env, outsider.clone()],
+        &Vec::new(&env),
+        &proof(&env, &levels, 0),
+    );
+    // A prova do capitão não serve para quem está fora da lista
+    assert!(client
+        .try_accept_invitation(&outsider, &team_name, &proof(&env, &levels, 0))
+        .is_err());
+    client.withdraw(&captain);
+
+    client.register_team(
+        &captain,
+        &team_name,
+        &vec![&env, member.clone()],
+        &Vec::new(&env),
+        &proof(&env, &levels, 0),
+    );
+    assert!(client.try_accept_invitation(&member, &team_name, &Vec::new(&env)).is_err());
+    client.accept_invitation(&member, &team_name, &proof(&env, &levels, 1));
+    assert_eq!(client.get_escrow_total(), 2 * ENTRY_FEE);
+}
+
+#[test]
+fn migrate_converts_a_v1_competition_to_the_current_layout() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, admin) = deploy(&env, |_| {});
+
+    // Layout v1: participantes no armazenamento de instância, sem as chaves criadas depois
+    let mut participants: Map<Symbol, Address> = Map::new(&env);
+    participants.set(username(&env, 0), Address::generate(&env));
+    participants.set(username(&env, 1), Address::generate(&env));
+    env.as_contract(&client.address, || {
+        let instance = env.storage().instance();
+        for key in [
+            DataKey::SchemaVersion,
+            DataKey::Oracle,
+            DataKey::Treasury,
+            DataKey::ResultsDeadline,
+            DataKey::Token,
+            DataKey::Escrow,
+        ] {
+            instance.remove(&key);
+        }
+        instance.set(&DataKey::Participants, &participants);
+        env.storage().persistent().remove(&DataKey::Participants);
+        env.storage().persistent().remove(&DataKey::FeesPaid);
+    });
+    assert_eq!(client.get_schema_version(), 1);
+
+    client.migrate();
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.get_schema_version(), 4);
+    env.as_contract(&client.address, || {
+        assert!(!env.storage().instance().has(&DataKey::Participants));
+        let moved: Map<Symbol, Address> = env.storage().persistent().get(&DataKey::Participants).unwrap();
+        assert_eq!(moved, participants);
+        let results_deadline: u64 = env.storage().instance().get(&DataKey::ResultsDeadline).unwrap();
+        assert_eq!(results_deadline, DEADLINE + DEFAULT_RESULTS_WINDOW);
+    });
+    assert_eq!(client.get_oracle(), admin);
+    assert_eq!(client.get_treasury(), admin);
+    assert_eq!(client.get_token(), Address::from_string(&SdkString::from_str(&env, LEGACY_XLM_TOKEN)));
+
+    // A custódia é reconstruída com a taxa base de cada inscrição antiga
+    assert_eq!(client.get_escrow_total(), 2 * ENTRY_FEE);
+    assert!(client.try_migrate().is_err());
+}
+
+#[test]
+fn migrate_requires_the_same_authority_as_the_upgrade() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _) = deploy_with_factory(&env, 0, |_| {});
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&DataKey::SchemaVersion, &3u32);
+    });
+
+    // Competições da fábrica são migradas por ela, não pelo admin
+    let admin = client.get_admin();
+    let invoke = MockAuthInvoke {
+        contract: &client.address,
+        fn_name: "migrate",
+        args: ().into_val(&env),
+        sub_invokes: &[],
+    };
+    assert!(client.mock_auths(&[MockAuth { address: &admin, invoke: &invoke }]).try_migrate().is_err());
+
+    env.mock_all_auths();
+    let factory = client.get_factory().unwrap();
+    client.migrate();
+    assert_eq!(env.auths()[0].0, factory);
+    assert_eq!(client.get_schema_version(), 4);
+}
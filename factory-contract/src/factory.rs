@@ -1,15 +1,448 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, Address, Env, Vec, BytesN, Symbol, IntoVal
+    contract, contractevent, contractimpl, contracttype, symbol_short, xdr::ToXdr, Address, Bytes,
+    Env, Error, Vec, BytesN, Symbol, IntoVal, String as SdkString,
 };
 
+mod registry;
+mod series;
+mod templates;
+mod tokens;
+mod treasury;
+
+pub use registry::{CompetitionRecord, CompetitionStatus, LifecycleEvent, PlatformStats};
+pub use series::Series;
+pub use templates::{Template, TemplateOverrides};
+pub use tokens::TokenInfo;
+
+// Versão do layout de armazenamento da fábrica, convertida por `migrate` após um upgrade.
+// v1: lista `Competitions` no armazenamento de instância.
+// v2: registro com metadados no armazenamento persistente (ver `registry`).
+// v3: índice do registro por status.
+const SCHEMA_VERSION: u32 = 3;
+
+// Teto da taxa da plataforma cobrada das competições, em basis points.
+const MAX_PLATFORM_FEE_BPS: u32 = 2000;
+
+// Wrapper do XLM na testnet, o token de todas as competições do layout v1.
+const LEGACY_XLM_TOKEN: &str = "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC";
+
 // Enumeração para as chaves de armazenamento da fábrica.
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
     Admin,
     WasmHash,
+    // Layout v1, lido apenas por `migrate`
     Competitions,
+    ChildWasmHash(Address),
+    PendingAdmin,
+    SchemaVersion,
+    Paused,
+    DeployCount(Address),
+    CreatorAllowlistEnabled,
+    AllowedCreator(Address),
+    CompetitionCount,
+    CompetitionAt(u32),
+    Record(Address),
+    CreatorCount(Address),
+    CreatorCompetitionAt(Address, u32),
+    GameCount(Symbol),
+    GameCompetitionAt(Symbol, u32),
+    Stats,
+    TokenVolume(Address),
+    Template(Symbol),
+    TemplateNames,
+    SeriesCount,
+    Series(u32),
+    SeriesInstance(u32, u32),
+    PreviousInstance(Address),
+    PlatformFeeBps,
+    TreasuryBalance(Address),
+    LifetimeFees(Address),
+    CompetitionFees(Address),
+    AllowedTokens,
+    AllowedToken(Address),
+    StatusCount(CompetitionStatus),
+    StatusCompetitionAt(CompetitionStatus, u32),
+    StatusPosition(Address),
+}
+
+// Parâmetros passados ao construtor da competição. Espelha o tipo de mesmo nome no
+// contrato de competição, que é serializado pelos nomes dos campos.
+#[contracttype]
+#[derive(Clone)]
+pub struct CompetitionConfig {
+    pub token: Address,
+    pub entry_fee: i128,
+    pub fee_schedule: Vec<FeeStep>,
+    pub payout_rules: PayoutCurve,
+    pub payout_brackets: Vec<PayoutBracket>,
+    pub fixed_prizes: Vec<i128>,
+    pub remainder_policy: RemainderPolicy,
+    // Sempre vazio: competições da fábrica pagam a tesouraria da plataforma
+    pub treasury: Option<Address>,
+    // 0 = competição individual
+    pub team_size: u32,
+    pub team_fee_mode: TeamFeeMode,
+    pub allowlist_root: Option<BytesN<32>>,
+    pub allowlist_leaf: AllowlistLeaf,
+    pub deadline: u64,
+    pub min_participants: u32,
+    // 0 = sem limite
+    pub max_participants: u32,
+    pub results_deadline: u64,
+    // 0 = sem check-in
+    pub check_in_end: u64,
+    pub no_show_policy: NoShowPolicy,
+    pub disqualification_policy: DisqualificationPolicy,
+}
+
+// Espelha `PayoutCurve` do contrato de competição, que expande as curvas em pontos-base.
+#[contracttype]
+#[derive(Clone)]
+pub enum PayoutCurve {
+    Table(Vec<u32>),
+    WinnerTakesAll,
+    TopNEqual(u32),
+    Linear(u32),
+    Geometric(u32, u32),
+}
+
+// Espelha `AllowlistLeaf` do contrato de competição.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AllowlistLeaf {
+    Address,
+    Username,
+}
+
+// Espelha `TeamFeeMode` do contrato de competição.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TeamFeeMode {
+    CaptainPaysAll,
+    PerMember,
+}
+
+// Espelha `RemainderPolicy` do contrato de competição.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RemainderPolicy {
+    Admin,
+    Treasury,
+    ProRataWinners,
+    FirstPlace,
+    RollOver,
+}
+
+// Espelha `PayoutBracket` do contrato de competição.
+#[contracttype]
+#[derive(Clone)]
+pub struct PayoutBracket {
+    pub min_participants: u32,
+    pub payout_rules: PayoutCurve,
+}
+
+// Espelha `FeeStep` do contrato de competição.
+#[contracttype]
+#[derive(Clone)]
+pub struct FeeStep {
+    pub until: u64,
+    pub fee: i128,
+}
+
+// Espelha `NoShowPolicy` do contrato de competição.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NoShowPolicy {
+    Refund,
+    Forfeit,
+}
+
+// Espelha `DisqualificationPolicy` do contrato de competição.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DisqualificationPolicy {
+    Refund,
+    Forfeit,
+}
+
+// Eventos emitidos na troca de admin da fábrica
+#[contractevent]
+#[derive(Clone)]
+pub struct AdminProposed {
+    #[topic]
+    pub current: Address,
+    pub proposed: Address,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct AdminTransferred {
+    #[topic]
+    pub previous: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct Upgraded {
+    pub new_wasm_hash: BytesN<32>,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct Migrated {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct WasmHashUpdated {
+    pub new_wasm_hash: BytesN<32>,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct ChildUpgraded {
+    #[topic]
+    pub competition: Address,
+    pub new_wasm_hash: BytesN<32>,
+}
+
+// A pausa da fábrica é global: bloqueia `create_competition` e é respeitada por todas as
+// competições implantadas por ela.
+#[contractevent]
+#[derive(Clone)]
+pub struct PauseChanged {
+    pub paused: bool,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct CreatorAllowlistUpdated {
+    #[topic]
+    pub creator: Address,
+    pub allowed: bool,
+}
+
+// Salt do deploy: sha256(creator || nonce || contador do criador). O contador garante salts
+// distintos mesmo que o criador repita o nonce.
+fn deploy_salt(env: &Env, creator: &Address, nonce: u64, counter: u64) -> BytesN<32> {
+    let mut data = Bytes::new(env);
+    data.append(&creator.clone().to_xdr(env));
+    data.extend_from_array(&nonce.to_be_bytes());
+    data.extend_from_array(&counter.to_be_bytes());
+    env.crypto().sha256(&data).into()
+}
+
+// Salt da próxima competição de `creator`, avançando o contador dele.
+fn next_creator_salt(env: &Env, creator: &Address, nonce: u64) -> BytesN<32> {
+    let count_key = DataKey::DeployCount(creator.clone());
+    let counter: u64 = env.storage().persistent().get(&count_key).unwrap_or(0);
+    env.storage().persistent().set(&count_key, &(counter + 1));
+    deploy_salt(env, creator, nonce, counter)
+}
+
+// Instâncias de séries usam salts próprios, derivados da série e da posição, para que
+// `spawn_next` não avance o contador do criador nem mude o que `predict_address` prevê.
+fn series_salt(env: &Env, series_id: u32, index: u32) -> BytesN<32> {
+    let mut data = Bytes::new(env);
+    data.append(&symbol_short!("series").to_xdr(env));
+    data.extend_from_array(&series_id.to_be_bytes());
+    data.extend_from_array(&index.to_be_bytes());
+    env.crypto().sha256(&data).into()
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct TemplateUpdated {
+    #[topic]
+    pub name: Symbol,
+    pub removed: bool,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct SeriesCreated {
+    #[topic]
+    pub series_id: u32,
+    pub template: Symbol,
+    pub period: u64,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct SeriesSpawned {
+    #[topic]
+    pub series_id: u32,
+    pub competition: Address,
+    pub index: u32,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct PlatformFeeUpdated {
+    pub platform_fee_bps: u32,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct FeeDeposited {
+    #[topic]
+    pub competition: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct TreasuryWithdrawn {
+    #[topic]
+    pub token: Address,
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct TokenAllowlistUpdated {
+    #[topic]
+    pub token: Address,
+    pub allowed: bool,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct CompetitionStatusChanged {
+    #[topic]
+    pub competition: Address,
+    pub status: CompetitionStatus,
+}
+
+// O criador sempre autoriza a criação, e o admin da competição precisa consentir, exceto
+// quando a fábrica cria em nome dele.
+fn authorize_creation(env: &Env, creator: &Address, comp_admin: &Address) {
+    creator.require_auth();
+
+    let factory_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    if comp_admin != creator && *creator != factory_admin {
+        comp_admin.require_auth();
+    }
+    if *creator != factory_admin
+        && !CompetitionFactory::is_creator_allowed(env.clone(), creator.clone())
+    {
+        panic!("Creator is not allowed");
+    }
+}
+
+// Fluxo comum de deploy e registro; a autorização fica a cargo de quem chama.
+fn deploy_competition(
+    env: &Env,
+    creator: Address,
+    salt: BytesN<32>,
+    comp_admin: Address,
+    config: CompetitionConfig,
+    game_tag: Symbol,
+) -> Address {
+    if CompetitionFactory::is_paused(env.clone()) {
+        panic!("Factory is paused");
+    }
+    tokens::require_allowed(env, &config.token, config.entry_fee);
+    for step in config.fee_schedule.iter() {
+        tokens::require_allowed(env, &config.token, step.fee);
+    }
+
+    let wasm_hash: BytesN<32> = env.storage().instance().get(&DataKey::WasmHash).unwrap();
+
+    // A competição é configurada pelo construtor, no mesmo deploy
+    let factory = Some(env.current_contract_address());
+    let platform_fee_bps = CompetitionFactory::get_platform_fee(env.clone());
+    let token = config.token.clone();
+    let entry_fee = config.entry_fee;
+    let new_contract_address = env.deployer().with_current_contract(salt).deploy_v2(
+        wasm_hash.clone(),
+        (comp_admin, config, factory, platform_fee_bps),
+    );
+
+    registry::add(
+        env,
+        &CompetitionRecord {
+            address: new_contract_address.clone(),
+            creator,
+            created_at: env.ledger().timestamp(),
+            token,
+            entry_fee,
+            game_tag,
+            status: CompetitionStatus::Open,
+            wasm_hash,
+            participant_count: 0,
+        },
+    );
+
+    new_contract_address
+}
+
+// Resolve os prazos relativos do template contra o timestamp atual do ledger. Campos
+// preenchidos em `overrides` têm precedência sobre o template.
+fn deploy_from_template(
+    env: &Env,
+    creator: Address,
+    salt: BytesN<32>,
+    comp_admin: Address,
+    template: Template,
+    overrides: TemplateOverrides,
+) -> Address {
+    let registration_duration = overrides
+        .registration_duration
+        .unwrap_or(template.registration_duration);
+    let results_window = overrides.results_window.unwrap_or(template.results_window);
+    let deadline = env.ledger().timestamp().saturating_add(registration_duration);
+    let results_deadline = deadline.saturating_add(results_window);
+    // A janela de check-in começa no fim das inscrições
+    let check_in_end = match template.check_in_window {
+        0 => 0,
+        check_in_window => deadline.saturating_add(check_in_window),
+    };
+
+    let config = CompetitionConfig {
+        token: template.token,
+        entry_fee: overrides.entry_fee.unwrap_or(template.entry_fee),
+        // Templates usam taxa única
+        fee_schedule: Vec::new(env),
+        payout_rules: overrides
+            .payout_rules
+            .map(PayoutCurve::Table)
+            .unwrap_or(template.payout_rules),
+        payout_brackets: template.payout_brackets,
+        // Prêmios fixos dependem de patrocínio, então não fazem parte de templates
+        fixed_prizes: Vec::new(env),
+        remainder_policy: template.remainder_policy,
+        treasury: None,
+        team_size: template.team_size,
+        team_fee_mode: template.team_fee_mode,
+        // Competições privadas definem a allowlist depois, com `set_allowlist_root`
+        allowlist_root: None,
+        allowlist_leaf: AllowlistLeaf::Address,
+        deadline,
+        min_participants: overrides.min_participants.unwrap_or(template.min_participants),
+        max_participants: overrides.max_participants.unwrap_or(template.max_participants),
+        results_deadline,
+        check_in_end,
+        no_show_policy: template.no_show_policy,
+        disqualification_policy: template.disqualification_policy,
+    };
+
+    deploy_competition(
+        env,
+        creator,
+        salt,
+        comp_admin,
+        config,
+        overrides.game_tag.unwrap_or(template.game_tag),
+    )
 }
 
 #[contract]
@@ -17,48 +450,628 @@ pub struct CompetitionFactory;
 
 #[contractimpl]
 impl CompetitionFactory {
-    pub fn initialize(env: Env, admin: Address, wasm_hash: BytesN<32>) {
-        if env.storage().instance().has(&DataKey::Admin) {
-            panic!("Factory already initialized");
-        }
+    pub fn __constructor(env: Env, admin: Address, wasm_hash: BytesN<32>) {
         env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage().instance().set(&DataKey::WasmHash, &wasm_hash);
-        env.storage().instance().set(&DataKey::Competitions, &Vec::<Address>::new(&env));
+        env.storage().instance().set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
     }
 
     pub fn create_competition(
         env: Env,
+        creator: Address,
+        nonce: u64,
+        comp_admin: Address,
+        config: CompetitionConfig,
+        game_tag: Symbol,
+    ) -> Address {
+        authorize_creation(&env, &creator, &comp_admin);
+
+        let salt = next_creator_salt(&env, &creator, nonce);
+        deploy_competition(&env, creator, salt, comp_admin, config, game_tag)
+    }
+
+    pub fn create_from_template(
+        env: Env,
+        creator: Address,
+        nonce: u64,
+        name: Symbol,
         comp_admin: Address,
-        entry_fee: i128,
-        payout_rules: Vec<u32>,
-        deadline: u64,
-        min_participants: u32,
+        overrides: TemplateOverrides,
     ) -> Address {
-        let wasm_hash: BytesN<32> = env.storage().instance().get(&DataKey::WasmHash).unwrap();
-
-        let mut competitions: Vec<Address> =
-            env.storage().instance().get(&DataKey::Competitions).unwrap();
-        let salt = BytesN::from_array(&env, &[competitions.len() as u8; 32]);
-
-        let new_contract_address = env
-            .deployer()
-            .with_current_contract(salt)
-            .deploy_v2(wasm_hash, ());
-
-        // Call initialize function using raw contract invocation
-        let _ = env.invoke_contract::<()>(
-            &new_contract_address,
-            &Symbol::new(&env, "initialize"),
-            (comp_admin, entry_fee, payout_rules, deadline, min_participants).into_val(&env),
+        let template = templates::get(&env, &name).unwrap_or_else(|| panic!("Template not found"));
+        authorize_creation(&env, &creator, &comp_admin);
+
+        let salt = next_creator_salt(&env, &creator, nonce);
+        deploy_from_template(&env, creator, salt, comp_admin, template, overrides)
+    }
+
+    // Cria uma série recorrente (ex.: ladder diária) baseada em um template. Só o admin da
+    // fábrica cria séries; as instâncias são criadas depois por `spawn_next`.
+    pub fn create_series(env: Env, template: Symbol, period: u64, comp_admin: Address) -> u32 {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        authorize_creation(&env, &admin, &comp_admin);
+
+        let series_template =
+            templates::get(&env, &template).unwrap_or_else(|| panic!("Template not found"));
+        series::require_spawnable(&series_template);
+        if period == 0 {
+            panic!("Period must be greater than zero");
+        }
+
+        let series_id = series::next_id(&env);
+        series::save(
+            &env,
+            series_id,
+            &Series {
+                template: template.clone(),
+                period,
+                creator: admin,
+                comp_admin,
+                instance_count: 0,
+                last_instance: None,
+                last_spawned_at: 0,
+                registration_closes_at: 0,
+                active: true,
+            },
         );
 
-        competitions.push_back(new_contract_address.clone());
-        env.storage().instance().set(&DataKey::Competitions, &competitions);
+        SeriesCreated { series_id, template, period }.publish(&env);
 
-        new_contract_address
+        series_id
     }
 
-    pub fn get_competitions(env: Env) -> Vec<Address> {
-        env.storage().instance().get(&DataKey::Competitions).unwrap()
+    // Qualquer um pode acionar a próxima instância, desde que as inscrições da anterior
+    // tenham fechado e o período da série tenha passado desde o último spawn.
+    pub fn spawn_next(env: Env, series_id: u32) -> Address {
+        let mut current =
+            series::get(&env, series_id).unwrap_or_else(|| panic!("Series not found"));
+        if !current.active {
+            panic!("Series has ended");
+        }
+
+        let now = env.ledger().timestamp();
+        if current.last_instance.is_some() {
+            if now <= current.registration_closes_at {
+                panic!("Previous instance registration is still open");
+            }
+            if now < current.last_spawned_at.saturating_add(current.period) {
+                panic!("Series period has not elapsed");
+            }
+        }
+
+        // O template pode ter mudado desde a criação da série
+        let template = templates::get(&env, &current.template)
+            .unwrap_or_else(|| panic!("Template not found"));
+        series::require_spawnable(&template);
+        let registration_duration = template.registration_duration;
+        let salt = series_salt(&env, series_id, current.instance_count);
+
+        let competition = deploy_from_template(
+            &env,
+            current.creator.clone(),
+            salt,
+            current.comp_admin.clone(),
+            template,
+            TemplateOverrides {
+                entry_fee: None,
+                payout_rules: None,
+                registration_duration: None,
+                results_window: None,
+                min_participants: None,
+                max_participants: None,
+                game_tag: None,
+            },
+        );
+
+        series::push_instance(
+            &env,
+            series_id,
+            current.instance_count,
+            &competition,
+            &current.last_instance,
+        );
+
+        current.instance_count += 1;
+        current.last_instance = Some(competition.clone());
+        current.last_spawned_at = now;
+        current.registration_closes_at = now.saturating_add(registration_duration);
+        series::save(&env, series_id, &current);
+
+        SeriesSpawned {
+            series_id,
+            competition: competition.clone(),
+            index: current.instance_count - 1,
+        }
+        .publish(&env);
+
+        competition
+    }
+
+    pub fn end_series(env: Env, series_id: u32) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let mut current =
+            series::get(&env, series_id).unwrap_or_else(|| panic!("Series not found"));
+        current.active = false;
+        series::save(&env, series_id, &current);
+    }
+
+    pub fn get_series(env: Env, series_id: u32) -> Series {
+        series::get(&env, series_id).unwrap_or_else(|| panic!("Series not found"))
+    }
+
+    // Histórico paginado das instâncias de uma série, da mais antiga para a mais recente.
+    pub fn get_series_instances(
+        env: Env,
+        series_id: u32,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<Address> {
+        series::instances(&env, series_id, offset, limit)
     }
-}
\ No newline at end of file
+
+    // Instância anterior da mesma série, se houver.
+    pub fn get_previous_instance(env: Env, competition: Address) -> Option<Address> {
+        series::previous(&env, &competition)
+    }
+
+    pub fn set_template(env: Env, name: Symbol, template: Template) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        templates::validate(&template);
+        templates::set(&env, &name, &template);
+
+        TemplateUpdated { name, removed: false }.publish(&env);
+    }
+
+    pub fn remove_template(env: Env, name: Symbol) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        templates::remove(&env, &name);
+
+        TemplateUpdated { name, removed: true }.publish(&env);
+    }
+
+    pub fn get_template(env: Env, name: Symbol) -> Template {
+        templates::get(&env, &name).unwrap_or_else(|| panic!("Template not found"))
+    }
+
+    pub fn get_template_names(env: Env) -> Vec<Symbol> {
+        templates::names(&env)
+    }
+
+    // Endereço que `create_competition(creator, nonce, ...)` vai gerar se for a próxima
+    // competição implantada por `creator`. Permite montar links de convite antes do deploy.
+    pub fn predict_address(env: Env, creator: Address, nonce: u64) -> Address {
+        let counter: u64 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::DeployCount(creator.clone()))
+            .unwrap_or(0);
+        let salt = deploy_salt(&env, &creator, nonce, counter);
+
+        env.deployer().with_current_contract(salt).deployed_address()
+    }
+
+    // Chamado pelas próprias competições a cada transição do ciclo de vida. Só filhas
+    // registradas por esta fábrica podem reportar, e cada uma apenas sobre si mesma.
+    pub fn report_lifecycle(env: Env, competition: Address, event: LifecycleEvent) {
+        competition.require_auth();
+
+        let mut record = registry::get(&env, &competition)
+            .unwrap_or_else(|| panic!("Competition not deployed by this factory"));
+
+        let status = match event {
+            LifecycleEvent::Joined(participant_count) => {
+                record.participant_count = participant_count;
+                registry::save(&env, &record);
+                return;
+            }
+            LifecycleEvent::Settled(prize_pool) => {
+                registry::add_volume(&env, &record.token, prize_pool);
+                CompetitionStatus::Settled
+            }
+            LifecycleEvent::Cancelled => CompetitionStatus::Cancelled,
+            LifecycleEvent::Refunded => CompetitionStatus::Refunded,
+        };
+
+        if registry::close(&env, &mut record, status.clone()) {
+            CompetitionStatusChanged { competition, status }.publish(&env);
+        }
+    }
+
+    // Adiciona (ou atualiza os metadados de) um token aceito nas competições.
+    pub fn set_allowed_token(env: Env, info: TokenInfo) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if info.min_entry_fee < 0 {
+            panic!("Minimum entry fee cannot be negative");
+        }
+        tokens::set(&env, &info);
+
+        TokenAllowlistUpdated { token: info.token, allowed: true }.publish(&env);
+    }
+
+    // Competições já criadas com o token não são afetadas.
+    pub fn remove_allowed_token(env: Env, token: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        tokens::remove(&env, &token);
+
+        TokenAllowlistUpdated { token, allowed: false }.publish(&env);
+    }
+
+    pub fn get_allowed_tokens(env: Env) -> Vec<TokenInfo> {
+        let mut result = Vec::new(&env);
+        for token in tokens::list(&env).iter() {
+            result.push_back(tokens::get(&env, &token).unwrap());
+        }
+        result
+    }
+
+    pub fn get_token_info(env: Env, token: Address) -> Option<TokenInfo> {
+        tokens::get(&env, &token)
+    }
+
+    // Taxa cobrada das competições criadas daqui em diante; as existentes mantêm a sua.
+    pub fn set_platform_fee(env: Env, platform_fee_bps: u32) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if platform_fee_bps > MAX_PLATFORM_FEE_BPS {
+            panic!("Platform fee is above the maximum");
+        }
+        env.storage().instance().set(&DataKey::PlatformFeeBps, &platform_fee_bps);
+
+        PlatformFeeUpdated { platform_fee_bps }.publish(&env);
+    }
+
+    pub fn get_platform_fee(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::PlatformFeeBps).unwrap_or(0)
+    }
+
+    // Chamado pela competição na liquidação, com a taxa da plataforma e, quando a competição
+    // manda a sobra para a tesouraria, com a sobra do pool. A fábrica transfere o valor da
+    // competição para si (a competição pré-autoriza a transferência) e credita apenas o que chegou.
+    pub fn deposit_fee(env: Env, competition: Address, amount: i128) {
+        competition.require_auth();
+
+        let record = registry::get(&env, &competition)
+            .unwrap_or_else(|| panic!("Competition not deployed by this factory"));
+        if amount <= 0 {
+            panic!("Fee amount must be positive");
+        }
+
+        let amount = treasury::collect(&env, &competition, &record.token, amount);
+        treasury::deposit(&env, &competition, &record.token, amount);
+
+        FeeDeposited { competition, token: record.token, amount }.publish(&env);
+    }
+
+    pub fn withdraw_treasury(env: Env, token: Address, to: Address, amount: i128) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        treasury::withdraw(&env, &token, &to, amount);
+
+        TreasuryWithdrawn { token, to, amount }.publish(&env);
+    }
+
+    pub fn get_treasury_balance(env: Env, token: Address) -> i128 {
+        treasury::balance(&env, &token)
+    }
+
+    // Total de taxas já recebidas, incluindo o que foi sacado.
+    pub fn get_lifetime_fees(env: Env, token: Address) -> i128 {
+        treasury::lifetime_fees(&env, &token)
+    }
+
+    pub fn get_competition_fees(env: Env, competition: Address) -> i128 {
+        treasury::competition_fees(&env, &competition)
+    }
+
+    pub fn get_platform_stats(env: Env) -> PlatformStats {
+        registry::stats(&env)
+    }
+
+    // Volume liquidado (soma dos prize pools) por token.
+    pub fn get_total_volume(env: Env, token: Address) -> i128 {
+        registry::volume(&env, &token)
+    }
+
+    // Consultas paginadas do registro; `limit` é limitado a `registry::MAX_PAGE_SIZE`.
+    pub fn get_competitions(env: Env, offset: u32, limit: u32) -> Vec<Address> {
+        registry::page(&env, offset, limit)
+    }
+
+    pub fn get_competition_count(env: Env) -> u32 {
+        registry::count(&env)
+    }
+
+    pub fn get_competition(env: Env, competition: Address) -> CompetitionRecord {
+        registry::get(&env, &competition).unwrap_or_else(|| panic!("Competition not found"))
+    }
+
+    pub fn get_competitions_by_creator(
+        env: Env,
+        creator: Address,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<CompetitionRecord> {
+        registry::page_by_creator(&env, &creator, offset, limit)
+    }
+
+    pub fn get_competitions_by_game(
+        env: Env,
+        game_tag: Symbol,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<CompetitionRecord> {
+        registry::page_by_game(&env, &game_tag, offset, limit)
+    }
+
+    pub fn get_competitions_by_status(
+        env: Env,
+        status: CompetitionStatus,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<CompetitionRecord> {
+        registry::page_by_status(&env, &status, offset, limit)
+    }
+
+    pub fn get_competition_count_by_status(env: Env, status: CompetitionStatus) -> u32 {
+        registry::count_by_status(&env, &status)
+    }
+
+    // Define o WASM usado nas próximas competições; as já implantadas não mudam.
+    pub fn set_wasm_hash(env: Env, new_wasm_hash: BytesN<32>) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::WasmHash, &new_wasm_hash);
+
+        WasmHashUpdated { new_wasm_hash }.publish(&env);
+    }
+
+    pub fn get_wasm_hash(env: Env) -> BytesN<32> {
+        env.storage().instance().get(&DataKey::WasmHash).unwrap()
+    }
+
+    // Atualiza o código das competições nas posições `offset..offset + limit` do registro que
+    // ainda não foram encerradas e migra o armazenamento delas. Competições já liquidadas ou
+    // reembolsadas, e as que não aceitam o upgrade, são ignoradas. Retorna quantas competições
+    // foram atualizadas.
+    pub fn upgrade_children(env: Env, new_hash: BytesN<32>, offset: u32, limit: u32) -> u32 {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let end = offset.saturating_add(limit).min(registry::count(&env));
+
+        let mut upgraded: u32 = 0;
+        for i in offset..end {
+            let competition = registry::address_at(&env, i);
+            let mut record = registry::get(&env, &competition).unwrap();
+            if record.wasm_hash == new_hash || record.status != CompetitionStatus::Open {
+                continue;
+            }
+
+            // Competições anteriores ao upgrade pela fábrica não têm `is_active` nem
+            // `factory_upgrade`; a falha de uma filha não pode reverter o lote inteiro
+            let is_active = env.try_invoke_contract::<bool, Error>(
+                &competition,
+                &Symbol::new(&env, "is_active"),
+                Vec::new(&env),
+            );
+            if is_active != Ok(Ok(true)) {
+                continue;
+            }
+
+            let result = env.try_invoke_contract::<(), Error>(
+                &competition,
+                &Symbol::new(&env, "factory_upgrade"),
+                (new_hash.clone(),).into_val(&env),
+            );
+            if result.is_err() {
+                continue;
+            }
+            // O código novo já vale nesta chamada. Filhas no layout atual recusam a migração,
+            // o que não desfaz o upgrade
+            let _ = env.try_invoke_contract::<(), Error>(
+                &competition,
+                &Symbol::new(&env, "migrate"),
+                Vec::new(&env),
+            );
+            record.wasm_hash = new_hash.clone();
+            registry::save(&env, &record);
+            upgraded += 1;
+
+            ChildUpgraded { competition, new_wasm_hash: new_hash.clone() }.publish(&env);
+        }
+
+        upgraded
+    }
+
+    pub fn get_child_wasm_hash(env: Env, competition: Address) -> Option<BytesN<32>> {
+        registry::get(&env, &competition).map(|record| record.wasm_hash)
+    }
+
+    // Com a allowlist ativa, apenas criadores aprovados (e o admin) podem criar competições.
+    pub fn set_creator_allowlist_enabled(env: Env, enabled: bool) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::CreatorAllowlistEnabled, &enabled);
+    }
+
+    pub fn allow_creator(env: Env, creator: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.storage().persistent().set(&DataKey::AllowedCreator(creator.clone()), &true);
+
+        CreatorAllowlistUpdated { creator, allowed: true }.publish(&env);
+    }
+
+    pub fn revoke_creator(env: Env, creator: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.storage().persistent().remove(&DataKey::AllowedCreator(creator.clone()));
+
+        CreatorAllowlistUpdated { creator, allowed: false }.publish(&env);
+    }
+
+    pub fn is_creator_allowed(env: Env, creator: Address) -> bool {
+        let enabled: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::CreatorAllowlistEnabled)
+            .unwrap_or(false);
+        if !enabled {
+            return true;
+        }
+
+        env.storage().persistent().has(&DataKey::AllowedCreator(creator))
+    }
+
+    pub fn pause(env: Env) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Paused, &true);
+
+        PauseChanged { paused: true }.publish(&env);
+    }
+
+    pub fn unpause(env: Env) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Paused, &false);
+
+        PauseChanged { paused: false }.publish(&env);
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().get(&DataKey::Paused).unwrap_or(false)
+    }
+
+    pub fn propose_admin(env: Env, new_admin: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::PendingAdmin, &new_admin);
+
+        AdminProposed { current: admin, proposed: new_admin }.publish(&env);
+    }
+
+    pub fn accept_admin(env: Env) {
+        let pending_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .unwrap_or_else(|| panic!("No pending admin"));
+        pending_admin.require_auth();
+
+        let previous: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        env.storage().instance().set(&DataKey::Admin, &pending_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        AdminTransferred { previous, new_admin: pending_admin }.publish(&env);
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+        Upgraded { new_wasm_hash }.publish(&env);
+    }
+
+    pub fn migrate(env: Env) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let from_version = Self::get_schema_version(env.clone());
+        if from_version >= SCHEMA_VERSION {
+            panic!("Nothing to migrate");
+        }
+
+        let mut version = from_version;
+        if version == 1 {
+            // O layout v1 não guardava metadados: as competições antigas entram no registro
+            // com o admin como criador, o token XLM e a tag `legacy`.
+            let competitions: Vec<Address> = env
+                .storage()
+                .instance()
+                .get(&DataKey::Competitions)
+                .unwrap_or_else(|| Vec::new(&env));
+            let default_hash: BytesN<32> =
+                env.storage().instance().get(&DataKey::WasmHash).unwrap();
+            let legacy_token = Address::from_string(&SdkString::from_str(&env, LEGACY_XLM_TOKEN));
+
+            for competition in competitions.iter() {
+                let hash_key = DataKey::ChildWasmHash(competition.clone());
+                let wasm_hash: BytesN<32> = env
+                    .storage()
+                    .persistent()
+                    .get(&hash_key)
+                    .unwrap_or_else(|| default_hash.clone());
+                env.storage().persistent().remove(&hash_key);
+
+                registry::add(
+                    &env,
+                    &CompetitionRecord {
+                        address: competition,
+                        creator: admin.clone(),
+                        created_at: 0,
+                        token: legacy_token.clone(),
+                        entry_fee: 0,
+                        game_tag: symbol_short!("legacy"),
+                        status: CompetitionStatus::Open,
+                        wasm_hash,
+                        participant_count: 0,
+                    },
+                );
+            }
+            env.storage().instance().remove(&DataKey::Competitions);
+            version = 2;
+        }
+        if version == 2 {
+            // Registros do layout v1 já entram indexados por `registry::add` no passo anterior
+            for i in 0..registry::count(&env) {
+                let competition = registry::address_at(&env, i);
+                if !registry::is_status_indexed(&env, &competition) {
+                    let record = registry::get(&env, &competition).unwrap();
+                    registry::index_status(&env, &competition, &record.status);
+                }
+            }
+            version = 3;
+        }
+
+        env.storage().instance().set(&DataKey::SchemaVersion, &version);
+
+        Migrated { from_version, to_version: version }.publish(&env);
+    }
+
+    pub fn get_schema_version(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::SchemaVersion).unwrap_or(1)
+    }
+}
+
+#[cfg(test)]
+mod test;
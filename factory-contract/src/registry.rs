@@ -0,0 +1,300 @@
+use soroban_sdk::{contracttype, Address, BytesN, Env, Symbol, Vec};
+
+use crate::DataKey;
+
+// Limite de itens por página nas consultas do registro.
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CompetitionStatus {
+    Open,
+    Settled,
+    Cancelled,
+    Refunded,
+}
+
+// Metadados de cada competição implantada pela fábrica.
+#[contracttype]
+#[derive(Clone)]
+pub struct CompetitionRecord {
+    pub address: Address,
+    pub creator: Address,
+    pub created_at: u64,
+    pub token: Address,
+    pub entry_fee: i128,
+    pub game_tag: Symbol,
+    pub status: CompetitionStatus,
+    pub wasm_hash: BytesN<32>,
+    pub participant_count: u32,
+}
+
+// Transições reportadas pelas competições filhas via `report_lifecycle`.
+#[contracttype]
+#[derive(Clone)]
+pub enum LifecycleEvent {
+    Joined(u32),
+    Settled(i128),
+    Cancelled,
+    Refunded,
+}
+
+// Agregados da plataforma, atualizados a cada transição reportada pelas filhas.
+#[contracttype]
+#[derive(Clone, Default)]
+pub struct PlatformStats {
+    pub active_competitions: u32,
+    pub settled_competitions: u32,
+    pub cancelled_competitions: u32,
+    pub refunded_competitions: u32,
+}
+
+// O registro fica no armazenamento persistente, uma entrada por competição, com índices
+// sequenciais globais, por criador, por jogo e por status para permitir paginação.
+pub fn add(env: &Env, record: &CompetitionRecord) {
+    let storage = env.storage().persistent();
+
+    let index = count(env);
+    storage.set(&DataKey::CompetitionAt(index), &record.address);
+    env.storage()
+        .instance()
+        .set(&DataKey::CompetitionCount, &(index + 1));
+
+    let creator_index: u32 = storage
+        .get(&DataKey::CreatorCount(record.creator.clone()))
+        .unwrap_or(0);
+    storage.set(
+        &DataKey::CreatorCompetitionAt(record.creator.clone(), creator_index),
+        &record.address,
+    );
+    storage.set(
+        &DataKey::CreatorCount(record.creator.clone()),
+        &(creator_index + 1),
+    );
+
+    let game_index: u32 = storage
+        .get(&DataKey::GameCount(record.game_tag.clone()))
+        .unwrap_or(0);
+    storage.set(
+        &DataKey::GameCompetitionAt(record.game_tag.clone(), game_index),
+        &record.address,
+    );
+    storage.set(
+        &DataKey::GameCount(record.game_tag.clone()),
+        &(game_index + 1),
+    );
+
+    save(env, record);
+    index_status(env, &record.address, &record.status);
+
+    if record.status == CompetitionStatus::Open {
+        let mut platform_stats = stats(env);
+        platform_stats.active_competitions += 1;
+        save_stats(env, &platform_stats);
+    }
+}
+
+// Encerra uma competição aberta com o status final. Retorna `false` se ela já estava encerrada,
+// já que `refund_all` ainda pode rodar depois que os reembolsos individuais esvaziaram a custódia.
+pub fn close(env: &Env, record: &mut CompetitionRecord, status: CompetitionStatus) -> bool {
+    if record.status != CompetitionStatus::Open {
+        return false;
+    }
+
+    let mut platform_stats = stats(env);
+    platform_stats.active_competitions = platform_stats.active_competitions.saturating_sub(1);
+    match status {
+        CompetitionStatus::Settled => platform_stats.settled_competitions += 1,
+        CompetitionStatus::Cancelled => platform_stats.cancelled_competitions += 1,
+        CompetitionStatus::Refunded => platform_stats.refunded_competitions += 1,
+        CompetitionStatus::Open => {}
+    }
+    save_stats(env, &platform_stats);
+
+    unindex_status(env, &record.address, &record.status);
+    index_status(env, &record.address, &status);
+    record.status = status;
+    save(env, record);
+    true
+}
+
+// O índice por status é uma lista densa por status. Como o status muda, cada competição guarda
+// a própria posição, e a remoção move a última da lista para o lugar vago.
+pub fn index_status(env: &Env, competition: &Address, status: &CompetitionStatus) {
+    let storage = env.storage().persistent();
+
+    let index: u32 = storage
+        .get(&DataKey::StatusCount(status.clone()))
+        .unwrap_or(0);
+    storage.set(&DataKey::StatusCompetitionAt(status.clone(), index), competition);
+    storage.set(&DataKey::StatusPosition(competition.clone()), &index);
+    storage.set(&DataKey::StatusCount(status.clone()), &(index + 1));
+}
+
+fn unindex_status(env: &Env, competition: &Address, status: &CompetitionStatus) {
+    let storage = env.storage().persistent();
+
+    // Registros de antes do índice ficam de fora até o `migrate` da fábrica
+    let Some(position) = storage.get::<_, u32>(&DataKey::StatusPosition(competition.clone()))
+    else {
+        return;
+    };
+    let last_index: u32 = storage
+        .get::<_, u32>(&DataKey::StatusCount(status.clone()))
+        .unwrap()
+        - 1;
+    if position != last_index {
+        let last: Address = storage
+            .get(&DataKey::StatusCompetitionAt(status.clone(), last_index))
+            .unwrap();
+        storage.set(&DataKey::StatusCompetitionAt(status.clone(), position), &last);
+        storage.set(&DataKey::StatusPosition(last), &position);
+    }
+    storage.remove(&DataKey::StatusCompetitionAt(status.clone(), last_index));
+    storage.remove(&DataKey::StatusPosition(competition.clone()));
+    storage.set(&DataKey::StatusCount(status.clone()), &last_index);
+}
+
+pub fn is_status_indexed(env: &Env, competition: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::StatusPosition(competition.clone()))
+}
+
+pub fn stats(env: &Env) -> PlatformStats {
+    env.storage()
+        .instance()
+        .get(&DataKey::Stats)
+        .unwrap_or_default()
+}
+
+fn save_stats(env: &Env, platform_stats: &PlatformStats) {
+    env.storage().instance().set(&DataKey::Stats, platform_stats);
+}
+
+pub fn add_volume(env: &Env, token: &Address, amount: i128) {
+    let key = DataKey::TokenVolume(token.clone());
+    let volume: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+    env.storage().persistent().set(&key, &(volume + amount));
+}
+
+pub fn volume(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::TokenVolume(token.clone()))
+        .unwrap_or(0)
+}
+
+pub fn save(env: &Env, record: &CompetitionRecord) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Record(record.address.clone()), record);
+}
+
+pub fn get(env: &Env, competition: &Address) -> Option<CompetitionRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Record(competition.clone()))
+}
+
+pub fn count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::CompetitionCount)
+        .unwrap_or(0)
+}
+
+pub fn address_at(env: &Env, index: u32) -> Address {
+    env.storage()
+        .persistent()
+        .get(&DataKey::CompetitionAt(index))
+        .unwrap()
+}
+
+pub fn page(env: &Env, offset: u32, limit: u32) -> Vec<Address> {
+    let end = page_end(offset, limit, count(env));
+    let mut result = Vec::new(env);
+    for i in offset..end {
+        result.push_back(address_at(env, i));
+    }
+    result
+}
+
+pub fn page_by_creator(
+    env: &Env,
+    creator: &Address,
+    offset: u32,
+    limit: u32,
+) -> Vec<CompetitionRecord> {
+    let storage = env.storage().persistent();
+    let total: u32 = storage
+        .get(&DataKey::CreatorCount(creator.clone()))
+        .unwrap_or(0);
+    let end = page_end(offset, limit, total);
+
+    let mut result = Vec::new(env);
+    for i in offset..end {
+        let address: Address = storage
+            .get(&DataKey::CreatorCompetitionAt(creator.clone(), i))
+            .unwrap();
+        result.push_back(get(env, &address).unwrap());
+    }
+    result
+}
+
+pub fn page_by_game(
+    env: &Env,
+    game_tag: &Symbol,
+    offset: u32,
+    limit: u32,
+) -> Vec<CompetitionRecord> {
+    let storage = env.storage().persistent();
+    let total: u32 = storage
+        .get(&DataKey::GameCount(game_tag.clone()))
+        .unwrap_or(0);
+    let end = page_end(offset, limit, total);
+
+    let mut result = Vec::new(env);
+    for i in offset..end {
+        let address: Address = storage
+            .get(&DataKey::GameCompetitionAt(game_tag.clone(), i))
+            .unwrap();
+        result.push_back(get(env, &address).unwrap());
+    }
+    result
+}
+
+// A ordem dentro de um status não é a de criação: quando uma competição muda de status, a
+// última da lista ocupa o lugar dela.
+pub fn page_by_status(
+    env: &Env,
+    status: &CompetitionStatus,
+    offset: u32,
+    limit: u32,
+) -> Vec<CompetitionRecord> {
+    let storage = env.storage().persistent();
+    let total: u32 = storage
+        .get(&DataKey::StatusCount(status.clone()))
+        .unwrap_or(0);
+    let end = page_end(offset, limit, total);
+
+    let mut result = Vec::new(env);
+    for i in offset..end {
+        let address: Address = storage
+            .get(&DataKey::StatusCompetitionAt(status.clone(), i))
+            .unwrap();
+        result.push_back(get(env, &address).unwrap());
+    }
+    result
+}
+
+pub fn count_by_status(env: &Env, status: &CompetitionStatus) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::StatusCount(status.clone()))
+        .unwrap_or(0)
+}
+
+fn page_end(offset: u32, limit: u32, total: u32) -> u32 {
+    offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(total)
+}
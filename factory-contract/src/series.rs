@@ -0,0 +1,83 @@
+use soroban_sdk::{contracttype, Address, Env, Symbol, Vec};
+
+use crate::registry::MAX_PAGE_SIZE;
+use crate::{DataKey, RemainderPolicy, Template};
+
+// Série recorrente: um template instanciado a cada `period` segundos por `spawn_next`.
+#[contracttype]
+#[derive(Clone)]
+pub struct Series {
+    pub template: Symbol,
+    pub period: u64,
+    pub creator: Address,
+    pub comp_admin: Address,
+    pub instance_count: u32,
+    pub last_instance: Option<Address>,
+    pub last_spawned_at: u64,
+    pub registration_closes_at: u64,
+    pub active: bool,
+}
+
+// `spawn_next` não liga uma instância à seguinte (isso exige o admin da competição), então a
+// sobra de uma instância não teria para onde rolar.
+pub fn require_spawnable(template: &Template) {
+    if template.remainder_policy == RemainderPolicy::RollOver {
+        panic!("Series templates cannot roll over the remainder");
+    }
+}
+
+pub fn next_id(env: &Env) -> u32 {
+    let id: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::SeriesCount)
+        .unwrap_or(0);
+    env.storage().instance().set(&DataKey::SeriesCount, &(id + 1));
+    id
+}
+
+pub fn get(env: &Env, series_id: u32) -> Option<Series> {
+    env.storage().persistent().get(&DataKey::Series(series_id))
+}
+
+pub fn save(env: &Env, series_id: u32, series: &Series) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Series(series_id), series);
+}
+
+pub fn push_instance(
+    env: &Env,
+    series_id: u32,
+    index: u32,
+    competition: &Address,
+    previous: &Option<Address>,
+) {
+    let storage = env.storage().persistent();
+    storage.set(&DataKey::SeriesInstance(series_id, index), competition);
+    if let Some(previous) = previous {
+        storage.set(&DataKey::PreviousInstance(competition.clone()), previous);
+    }
+}
+
+pub fn instances(env: &Env, series_id: u32, offset: u32, limit: u32) -> Vec<Address> {
+    let total = get(env, series_id).map(|s| s.instance_count).unwrap_or(0);
+    let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(total);
+
+    let mut result = Vec::new(env);
+    for i in offset..end {
+        result.push_back(
+            env.storage()
+                .persistent()
+                .get(&DataKey::SeriesInstance(series_id, i))
+                .unwrap(),
+        );
+    }
+    result
+}
+
+pub fn previous(env: &Env, competition: &Address) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PreviousInstance(competition.clone()))
+}
@@ -0,0 +1,103 @@
+use soroban_sdk::{contracttype, Address, Env, Symbol, Vec};
+
+use crate::{
+    DataKey, DisqualificationPolicy, NoShowPolicy, PayoutBracket, PayoutCurve, RemainderPolicy,
+    TeamFeeMode,
+};
+
+// Configuração nomeada, mantida pelo admin da fábrica, para competições criadas com frequência.
+// Os prazos são relativos: `registration_duration` conta a partir da criação e
+// `results_window` a partir do fim das inscrições.
+#[contracttype]
+#[derive(Clone)]
+pub struct Template {
+    pub token: Address,
+    pub entry_fee: i128,
+    pub payout_rules: PayoutCurve,
+    // Tabelas por tamanho do campo; a competição as valida no construtor
+    pub payout_brackets: Vec<PayoutBracket>,
+    pub remainder_policy: RemainderPolicy,
+    // Jogadores por time, ex.: 5 para 5v5; 0 = individual
+    pub team_size: u32,
+    pub team_fee_mode: TeamFeeMode,
+    pub registration_duration: u64,
+    pub results_window: u64,
+    pub min_participants: u32,
+    // 0 = sem limite
+    pub max_participants: u32,
+    // Duração da janela de check-in após o fim das inscrições; 0 = sem check-in
+    pub check_in_window: u64,
+    pub no_show_policy: NoShowPolicy,
+    pub disqualification_policy: DisqualificationPolicy,
+    pub game_tag: Symbol,
+}
+
+// Campos opcionais que substituem os valores do template em `create_from_template`.
+#[contracttype]
+#[derive(Clone)]
+pub struct TemplateOverrides {
+    pub entry_fee: Option<i128>,
+    // Tabela bruta em pontos-base, usada como `PayoutCurve::Table`
+    pub payout_rules: Option<Vec<u32>>,
+    pub registration_duration: Option<u64>,
+    pub results_window: Option<u64>,
+    pub min_participants: Option<u32>,
+    pub max_participants: Option<u32>,
+    pub game_tag: Option<Symbol>,
+}
+
+pub fn validate(template: &Template) {
+    if template.entry_fee < 0 {
+        panic!("Entry fee cannot be negative");
+    }
+    if template.results_window == 0 {
+        panic!("Results window must be greater than zero");
+    }
+    if template.max_participants != 0 && template.max_participants < template.min_participants {
+        panic!("Max participants must be at least min participants");
+    }
+    if template.check_in_window >= template.results_window {
+        panic!("Check-in window must be shorter than the results window");
+    }
+}
+
+pub fn get(env: &Env, name: &Symbol) -> Option<Template> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Template(name.clone()))
+}
+
+pub fn set(env: &Env, name: &Symbol, template: &Template) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Template(name.clone()), template);
+
+    let mut template_names = names(env);
+    if !template_names.contains(name) {
+        template_names.push_back(name.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::TemplateNames, &template_names);
+    }
+}
+
+pub fn remove(env: &Env, name: &Symbol) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Template(name.clone()));
+
+    let mut template_names = names(env);
+    if let Some(index) = template_names.first_index_of(name) {
+        template_names.remove(index);
+        env.storage()
+            .instance()
+            .set(&DataKey::TemplateNames, &template_names);
+    }
+}
+
+pub fn names(env: &Env) -> Vec<Symbol> {
+    env.storage()
+        .instance()
+        .get(&DataKey::TemplateNames)
+        .unwrap_or_else(|| Vec::new(env))
+}
@@ -0,0 +1,721 @@
+#![cfg(test)]
+extern crate std;
+
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, Bytes, BytesN, Env, IntoVal, String as SdkString, Vec,
+};
+
+use crate::{
+    registry, AllowlistLeaf, CompetitionConfig, CompetitionFactory, CompetitionFactoryClient,
+    CompetitionRecord, CompetitionStatus, DataKey, DisqualificationPolicy, FeeStep, LifecycleEvent,
+    NoShowPolicy, PayoutCurve, RemainderPolicy, TeamFeeMode, Template, TemplateOverrides, TokenInfo,
+    LEGACY_XLM_TOKEN, SCHEMA_VERSION,
+};
+
+// Contrato de competição compilado. Gere o WASM antes de rodar os testes da fábrica:
+// `cargo build -p competition-contract --target wasm32v1-none --release`
+mod competition {
+    // O código gerado usa o alias `SdkString` do contrato de competição
+    use soroban_sdk::String as SdkString;
+
+    soroban_sdk::contractimport!(
+        file = "../target/wasm32v1-none/release/competition_contract.wasm"
+    );
+}
+
+const ENTRY_FEE: i128 = 100;
+const DEADLINE: u64 = 1_000;
+
+fn config(env: &Env, token: &Address) -> CompetitionConfig {
+    CompetitionConfig {
+        token: token.clone(),
+        entry_fee: ENTRY_FEE,
+        fee_schedule: Vec::new(env),
+        payout_rules: PayoutCurve::WinnerTakesAll,
+        payout_brackets: Vec::new(env),
+        fixed_prizes: Vec::new(env),
+        remainder_policy: RemainderPolicy::Treasury,
+        treasury: None,
+        team_size: 0,
+        team_fee_mode: TeamFeeMode::CaptainPaysAll,
+        allowlist_root: None,
+        allowlist_leaf: AllowlistLeaf::Address,
+        deadline: DEADLINE,
+        min_participants: 1,
+        max_participants: 0,
+        results_deadline: DEADLINE * 2,
+        check_in_end: 0,
+        no_show_policy: NoShowPolicy::Refund,
+        disqualification_policy: DisqualificationPolicy::Refund,
+    }
+}
+
+// Implanta a fábrica com o WASM da competição e um token aceito com taxa mínima de 1.
+fn setup<'a>(env: &Env) -> (CompetitionFactoryClient<'a>, Address, Address) {
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(env);
+    let wasm_hash = env.deployer().upload_contract_wasm(competition::WASM);
+    let factory_id = env.register(CompetitionFactory, (admin.clone(), wasm_hash));
+    let factory = CompetitionFactoryClient::new(env, &factory_id);
+
+    let token = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    factory.set_allowed_token(&TokenInfo {
+        token: token.clone(),
+        symbol: SdkString::from_str(env, "TST"),
+        decimals: 7,
+        min_entry_fee: 1,
+    });
+
+    (factory, admin, token)
+}
+
+// Competição com a configuração padrão, administrada pelo próprio criador.
+fn create(env: &Env, factory: &CompetitionFactoryClient, creator: &Address, token: &Address) -> Address {
+    factory.create_competition(creator, &0, creator, &config(env, token), &symbol_short!("chess"))
+}
+
+fn funded_player(env: &Env, token: &Address) -> Address {
+    let player = Address::generate(env);
+    StellarAssetClient::new(env, token).mint(&player, &ENTRY_FEE);
+    player
+}
+
+// Liquida a competição com um único jogador, que leva o pool.
+fn settle(env: &Env, competition: &Address, token: &Address) {
+    let client = competition::Client::new(env, competition);
+    let player = funded_player(env, token);
+    client.join(&player, &symbol_short!("alice"), &Vec::new(env));
+    client.distribute_prizes(&vec![env, symbol_short!("alice")]);
+}
+
+// O mesmo código com uma seção customizada no fim, para obter outro hash de WASM.
+fn upgraded_wasm_hash(env: &Env) -> BytesN<32> {
+    let mut wasm = Bytes::from_slice(env, competition::WASM);
+    wasm.extend_from_array(&[0, 2, 1, b'v']);
+    env.deployer().upload_contract_wasm(wasm)
+}
+
+// Filha que aceita a consulta de `is_active`, mas rejeita o upgrade.
+#[contract]
+struct BrokenChild;
+
+#[contractimpl]
+impl BrokenChild {
+    pub fn is_active(_env: Env) -> bool {
+        true
+    }
+
+    pub fn factory_upgrade(_env: Env, _new_wasm_hash: BytesN<32>) {
+        panic!("Upgrade rejected");
+    }
+}
+
+#[test]
+fn upgrade_children_skips_closed_and_failing_children() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+
+    let settled = create(&env, &factory, &admin, &token);
+    let open = create(&env, &factory, &admin, &token);
+    settle(&env, &settled, &token);
+
+    let broken = env.register(BrokenChild, ());
+    let original_hash = factory.get_wasm_hash();
+    env.as_contract(&factory.address, || {
+        registry::add(
+            &env,
+            &CompetitionRecord {
+                address: broken.clone(),
+                creator: admin.clone(),
+                created_at: 0,
+                token: token.clone(),
+                entry_fee: ENTRY_FEE,
+                game_tag: symbol_short!("chess"),
+                status: CompetitionStatus::Open,
+                wasm_hash: original_hash.clone(),
+                participant_count: 0,
+            },
+        );
+    });
+
+    // A falha de uma filha não reverte o lote
+    let new_hash = upgraded_wasm_hash(&env);
+    assert_eq!(factory.upgrade_children(&new_hash, &0, &10), 1);
+    assert_eq!(factory.get_child_wasm_hash(&open), Some(new_hash.clone()));
+    assert_eq!(factory.get_child_wasm_hash(&settled), Some(original_hash.clone()));
+    assert_eq!(factory.get_child_wasm_hash(&broken), Some(original_hash));
+
+    // A filha atualizada continua funcionando, e um novo lote não a atualiza de novo
+    assert!(competition::Client::new(&env, &open).is_active());
+    assert_eq!(factory.upgrade_children(&new_hash, &0, &10), 0);
+}
+
+#[test]
+fn upgrade_children_only_touches_the_requested_page() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+
+    let children: std::vec::Vec<Address> =
+        (0..3).map(|_| create(&env, &factory, &admin, &token)).collect();
+
+    let new_hash = upgraded_wasm_hash(&env);
+    assert_eq!(factory.upgrade_children(&new_hash, &1, &1), 1);
+    assert_eq!(factory.get_child_wasm_hash(&children[0]), Some(factory.get_wasm_hash()));
+    assert_eq!(factory.get_child_wasm_hash(&children[1]), Some(new_hash.clone()));
+    assert_eq!(factory.get_child_wasm_hash(&children[2]), Some(factory.get_wasm_hash()));
+
+    // Um offset além do registro não atualiza nada
+    assert_eq!(factory.upgrade_children(&new_hash, &10, &5), 0);
+}
+
+#[test]
+fn predicted_address_matches_the_deployed_competition() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+    let creator = Address::generate(&env);
+
+    let predicted = factory.predict_address(&creator, &7);
+    let deployed =
+        factory.create_competition(&creator, &7, &creator, &config(&env, &token), &symbol_short!("chess"));
+    assert_eq!(deployed, predicted);
+
+    // A previsão de outro criador não é afetada
+    let predicted = factory.predict_address(&admin, &7);
+    let deployed =
+        factory.create_competition(&admin, &7, &admin, &config(&env, &token), &symbol_short!("chess"));
+    assert_eq!(deployed, predicted);
+}
+
+#[test]
+fn one_creator_deploys_more_than_256_competitions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+
+    // O mesmo nonce é reutilizado: o contador do criador mantém os salts distintos
+    let mut deployed: std::vec::Vec<Address> = std::vec::Vec::new();
+    for _ in 0..260 {
+        let predicted = factory.predict_address(&admin, &0);
+        let competition = create(&env, &factory, &admin, &token);
+        assert_eq!(competition, predicted);
+        assert!(!deployed.contains(&competition));
+        deployed.push(competition);
+    }
+    assert_eq!(factory.get_competition_count(), 260);
+}
+
+// Cria a competição autorizada apenas por `signers`.
+fn try_create_signed_by(
+    env: &Env,
+    factory: &CompetitionFactoryClient,
+    creator: &Address,
+    comp_admin: &Address,
+    token: &Address,
+    signers: &[&Address],
+) -> bool {
+    let config = config(env, token);
+    let args = (creator.clone(), 0u64, comp_admin.clone(), config.clone(), symbol_short!("chess"));
+    let invoke = MockAuthInvoke {
+        contract: &factory.address,
+        fn_name: "create_competition",
+        args: args.into_val(env),
+        sub_invokes: &[],
+    };
+    let auths: std::vec::Vec<MockAuth> =
+        signers.iter().map(|address| MockAuth { address, invoke: &invoke }).collect();
+
+    factory
+        .mock_auths(&auths)
+        .try_create_competition(creator, &0, comp_admin, &config, &symbol_short!("chess"))
+        .is_ok()
+}
+
+#[test]
+fn comp_admin_must_consent_to_a_competition_created_by_someone_else() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, _, token) = setup(&env);
+    let creator = Address::generate(&env);
+    let comp_admin = Address::generate(&env);
+
+    assert!(!try_create_signed_by(&env, &factory, &creator, &comp_admin, &token, &[&creator]));
+    assert!(try_create_signed_by(&env, &factory, &creator, &comp_admin, &token, &[&creator, &comp_admin]));
+
+    // Quem administra a própria competição não precisa de um segundo consentimento
+    assert!(try_create_signed_by(&env, &factory, &creator, &creator, &token, &[&creator]));
+}
+
+#[test]
+fn factory_admin_creates_competitions_on_behalf_of_comp_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+    let comp_admin = Address::generate(&env);
+
+    assert!(try_create_signed_by(&env, &factory, &admin, &comp_admin, &token, &[&admin]));
+    assert!(!try_create_signed_by(&env, &factory, &admin, &comp_admin, &token, &[&comp_admin]));
+}
+
+#[test]
+fn creator_allowlist_restricts_who_creates_competitions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+    let creator = Address::generate(&env);
+
+    factory.set_creator_allowlist_enabled(&true);
+    assert!(!factory.is_creator_allowed(&creator));
+    assert!(factory
+        .try_create_competition(&creator, &0, &creator, &config(&env, &token), &symbol_short!("chess"))
+        .is_err());
+
+    // O admin da fábrica não precisa estar na allowlist
+    create(&env, &factory, &admin, &token);
+
+    factory.allow_creator(&creator);
+    create(&env, &factory, &creator, &token);
+
+    factory.revoke_creator(&creator);
+    assert!(factory
+        .try_create_competition(&creator, &1, &creator, &config(&env, &token), &symbol_short!("chess"))
+        .is_err());
+
+    // Desativada, a allowlist deixa de valer
+    factory.set_creator_allowlist_enabled(&false);
+    create(&env, &factory, &creator, &token);
+}
+
+fn addresses(records: &Vec<CompetitionRecord>) -> std::vec::Vec<Address> {
+    records.iter().map(|record| record.address).collect()
+}
+
+#[test]
+fn registry_pages_by_creator_game_and_position() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+    let creator = Address::generate(&env);
+
+    let chess = create(&env, &factory, &admin, &token);
+    let go = factory.create_competition(&creator, &0, &creator, &config(&env, &token), &symbol_short!("go"));
+    let chess_by_creator = create(&env, &factory, &creator, &token);
+
+    assert_eq!(factory.get_competition_count(), 3);
+    assert_eq!(factory.get_competitions(&0, &2), vec![&env, chess.clone(), go.clone()]);
+    assert_eq!(factory.get_competitions(&2, &2), vec![&env, chess_by_creator.clone()]);
+    assert_eq!(factory.get_competitions(&3, &2), Vec::new(&env));
+
+    let by_creator = factory.get_competitions_by_creator(&creator, &0, &10);
+    assert_eq!(addresses(&by_creator), [go.clone(), chess_by_creator.clone()]);
+    let second_page = factory.get_competitions_by_creator(&creator, &1, &1);
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page.get(0).unwrap().address, chess_by_creator);
+
+    let by_game = factory.get_competitions_by_game(&symbol_short!("chess"), &0, &10);
+    assert_eq!(addresses(&by_game), [chess, chess_by_creator]);
+    let record = factory.get_competition(&go);
+    assert_eq!(record.creator, creator);
+    assert_eq!(record.game_tag, symbol_short!("go"));
+    assert_eq!(record.entry_fee, ENTRY_FEE);
+}
+
+#[test]
+fn registry_page_size_is_capped() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+
+    for _ in 0..registry::MAX_PAGE_SIZE + 1 {
+        create(&env, &factory, &admin, &token);
+    }
+    assert_eq!(factory.get_competitions(&0, &u32::MAX).len(), registry::MAX_PAGE_SIZE);
+    assert_eq!(factory.get_competitions(&registry::MAX_PAGE_SIZE, &u32::MAX).len(), 1);
+}
+
+#[test]
+fn closing_a_competition_moves_the_last_one_into_its_status_slot() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+
+    let children: std::vec::Vec<Address> =
+        (0..3).map(|_| create(&env, &factory, &admin, &token)).collect();
+    let open = factory.get_competitions_by_status(&CompetitionStatus::Open, &0, &10);
+    assert_eq!(addresses(&open), children);
+
+    // A primeira sai do índice de abertas e a última ocupa a posição dela
+    competition::Client::new(&env, &children[0]).cancel();
+    let open = factory.get_competitions_by_status(&CompetitionStatus::Open, &0, &10);
+    assert_eq!(addresses(&open), [children[2].clone(), children[1].clone()]);
+    let cancelled = factory.get_competitions_by_status(&CompetitionStatus::Cancelled, &0, &10);
+    assert_eq!(addresses(&cancelled), [children[0].clone()]);
+    assert_eq!(factory.get_competition(&children[0]).status, CompetitionStatus::Cancelled);
+
+    // Remover a última da lista não move nenhuma outra
+    settle(&env, &children[1], &token);
+    let open = factory.get_competitions_by_status(&CompetitionStatus::Open, &0, &10);
+    assert_eq!(addresses(&open), [children[2].clone()]);
+    assert_eq!(factory.get_competition_count_by_status(&CompetitionStatus::Settled), 1);
+
+    let stats = factory.get_platform_stats();
+    assert_eq!(stats.active_competitions, 1);
+    assert_eq!(stats.cancelled_competitions, 1);
+    assert_eq!(stats.settled_competitions, 1);
+}
+
+#[test]
+fn report_lifecycle_rejects_contracts_not_deployed_by_the_factory() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+
+    let stranger = env.register(BrokenChild, ());
+    assert!(factory.try_report_lifecycle(&stranger, &LifecycleEvent::Cancelled).is_err());
+    assert!(factory.try_report_lifecycle(&Address::generate(&env), &LifecycleEvent::Joined(3)).is_err());
+
+    // Filhas registradas atualizam o próprio registro
+    let child = create(&env, &factory, &admin, &token);
+    let player = funded_player(&env, &token);
+    competition::Client::new(&env, &child).join(&player, &symbol_short!("alice"), &Vec::new(&env));
+    assert_eq!(factory.get_competition(&child).participant_count, 1);
+}
+
+fn template(env: &Env, token: &Address) -> Template {
+    Template {
+        token: token.clone(),
+        entry_fee: ENTRY_FEE,
+        payout_rules: PayoutCurve::WinnerTakesAll,
+        payout_brackets: Vec::new(env),
+        remainder_policy: RemainderPolicy::Treasury,
+        team_size: 0,
+        team_fee_mode: TeamFeeMode::CaptainPaysAll,
+        registration_duration: 100,
+        results_window: 500,
+        min_participants: 1,
+        max_participants: 0,
+        check_in_window: 50,
+        no_show_policy: NoShowPolicy::Refund,
+        disqualification_policy: DisqualificationPolicy::Refund,
+        game_tag: symbol_short!("chess"),
+    }
+}
+
+fn no_overrides() -> TemplateOverrides {
+    TemplateOverrides {
+        entry_fee: None,
+        payout_rules: None,
+        registration_duration: None,
+        results_window: None,
+        min_participants: None,
+        max_participants: None,
+        game_tag: None,
+    }
+}
+
+// Prazos gravados pelo construtor da competição: inscrições, resultados e check-in.
+fn deadlines(env: &Env, competition: &Address) -> (u64, u64, u64) {
+    env.as_contract(competition, || {
+        let storage = env.storage().instance();
+        (
+            storage.get(&competition::DataKey::Deadline).unwrap(),
+            storage.get(&competition::DataKey::ResultsDeadline).unwrap(),
+            storage.get(&competition::DataKey::CheckInEnd).unwrap(),
+        )
+    })
+}
+
+#[test]
+fn template_deadlines_are_relative_to_the_ledger_time() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+    factory.set_template(&symbol_short!("daily"), &template(&env, &token));
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = 5_000);
+    let first = factory.create_from_template(&admin, &0, &symbol_short!("daily"), &admin, &no_overrides());
+    assert_eq!(deadlines(&env, &first), (5_100, 5_600, 5_150));
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = 9_000);
+    let mut overrides = no_overrides();
+    overrides.registration_duration = Some(200);
+    overrides.results_window = Some(1_000);
+    overrides.entry_fee = Some(ENTRY_FEE * 2);
+    overrides.game_tag = Some(symbol_short!("blitz"));
+    let second = factory.create_from_template(&admin, &0, &symbol_short!("daily"), &admin, &overrides);
+    assert_eq!(deadlines(&env, &second), (9_200, 10_200, 9_250));
+
+    let record = factory.get_competition(&second);
+    assert_eq!(record.entry_fee, ENTRY_FEE * 2);
+    assert_eq!(record.game_tag, symbol_short!("blitz"));
+    assert_eq!(record.created_at, 9_000);
+}
+
+#[test]
+fn create_from_template_rejects_unknown_templates() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+    factory.set_template(&symbol_short!("daily"), &template(&env, &token));
+    factory.remove_template(&symbol_short!("daily"));
+
+    let result = factory.try_create_from_template(&admin, &0, &symbol_short!("daily"), &admin, &no_overrides());
+    assert!(result.is_err());
+    assert_eq!(factory.get_template_names(), Vec::new(&env));
+}
+
+#[test]
+fn spawn_next_waits_for_registration_to_close_and_the_period_to_pass() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+    factory.set_template(&symbol_short!("daily"), &template(&env, &token));
+    let series_id = factory.create_series(&symbol_short!("daily"), &1_000, &admin);
+
+    let first = factory.spawn_next(&series_id);
+
+    // As inscrições da primeira instância fecham em 100
+    env.ledger().with_mut(|ledger| ledger.timestamp = 100);
+    assert!(factory.try_spawn_next(&series_id).is_err());
+    env.ledger().with_mut(|ledger| ledger.timestamp = 999);
+    assert!(factory.try_spawn_next(&series_id).is_err());
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = 1_000);
+    let second = factory.spawn_next(&series_id);
+    assert_eq!(factory.get_series_instances(&series_id, &0, &10), vec![&env, first.clone(), second.clone()]);
+    assert_eq!(factory.get_previous_instance(&second), Some(first));
+
+    let series = factory.get_series(&series_id);
+    assert_eq!(series.instance_count, 2);
+    assert_eq!(series.registration_closes_at, 1_100);
+
+    factory.end_series(&series_id);
+    env.ledger().with_mut(|ledger| ledger.timestamp = 5_000);
+    assert!(factory.try_spawn_next(&series_id).is_err());
+}
+
+#[test]
+fn spawn_next_waits_for_registration_longer_than_the_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+    let mut long_registration = template(&env, &token);
+    long_registration.registration_duration = 2_000;
+    long_registration.results_window = 2_500;
+    factory.set_template(&symbol_short!("weekly"), &long_registration);
+    let series_id = factory.create_series(&symbol_short!("weekly"), &1_000, &admin);
+
+    factory.spawn_next(&series_id);
+    env.ledger().with_mut(|ledger| ledger.timestamp = 1_500);
+    assert!(factory.try_spawn_next(&series_id).is_err());
+    env.ledger().with_mut(|ledger| ledger.timestamp = 2_001);
+    factory.spawn_next(&series_id);
+}
+
+fn token_balance(env: &Env, token: &Address, holder: &Address) -> i128 {
+    TokenClient::new(env, token).balance(holder)
+}
+
+#[test]
+fn treasury_tracks_fees_per_token_and_competition() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+    factory.set_platform_fee(&1_000);
+
+    let child = create(&env, &factory, &admin, &token);
+    let other = create(&env, &factory, &admin, &token);
+    let client = competition::Client::new(&env, &child);
+    for name in [symbol_short!("alice"), symbol_short!("bob")] {
+        client.join(&funded_player(&env, &token), &name, &Vec::new(&env));
+    }
+    client.distribute_prizes(&vec![&env, symbol_short!("alice")]);
+
+    // 10% de um pool de 200
+    assert_eq!(factory.get_treasury_balance(&token), 20);
+    assert_eq!(factory.get_lifetime_fees(&token), 20);
+    assert_eq!(factory.get_competition_fees(&child), 20);
+    assert_eq!(factory.get_competition_fees(&other), 0);
+    assert_eq!(token_balance(&env, &token, &factory.address), 20);
+
+    let recipient = Address::generate(&env);
+    assert!(factory.try_withdraw_treasury(&token, &recipient, &0).is_err());
+    assert!(factory.try_withdraw_treasury(&token, &recipient, &21).is_err());
+    factory.withdraw_treasury(&token, &recipient, &15);
+
+    // O total histórico não muda com o saque
+    assert_eq!(factory.get_treasury_balance(&token), 5);
+    assert_eq!(factory.get_lifetime_fees(&token), 20);
+    assert_eq!(token_balance(&env, &token, &recipient), 15);
+
+    let other_token = env.register_stellar_asset_contract_v2(admin).address();
+    assert!(factory.try_withdraw_treasury(&other_token, &recipient, &1).is_err());
+}
+
+#[test]
+fn deposit_fee_rejects_contracts_not_deployed_by_the_factory() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, _, _) = setup(&env);
+
+    let stranger = env.register(BrokenChild, ());
+    assert!(factory.try_deposit_fee(&stranger, &10).is_err());
+}
+
+#[test]
+fn competitions_only_use_allowlisted_tokens_above_the_minimum_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+    let tag = symbol_short!("chess");
+
+    let unknown_token = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    assert!(factory.try_create_competition(&admin, &0, &admin, &config(&env, &unknown_token), &tag).is_err());
+
+    factory.set_allowed_token(&TokenInfo {
+        token: token.clone(),
+        symbol: SdkString::from_str(&env, "TST"),
+        decimals: 7,
+        min_entry_fee: ENTRY_FEE,
+    });
+    let mut below_minimum = config(&env, &token);
+    below_minimum.entry_fee = ENTRY_FEE - 1;
+    assert!(factory.try_create_competition(&admin, &0, &admin, &below_minimum, &tag).is_err());
+
+    // Cada degrau da tabela de taxas também respeita o mínimo
+    let mut early_bird = config(&env, &token);
+    early_bird.fee_schedule = vec![&env, FeeStep { until: DEADLINE / 2, fee: ENTRY_FEE / 2 }];
+    assert!(factory.try_create_competition(&admin, &0, &admin, &early_bird, &tag).is_err());
+
+    let existing = create(&env, &factory, &admin, &token);
+    factory.remove_allowed_token(&token);
+    assert!(factory.try_create_competition(&admin, &0, &admin, &config(&env, &token), &tag).is_err());
+    assert_eq!(factory.get_allowed_tokens().len(), 0);
+
+    // Competições já criadas com o token não são afetadas
+    let player = funded_player(&env, &token);
+    competition::Client::new(&env, &existing).join(&player, &symbol_short!("alice"), &Vec::new(&env));
+}
+
+#[test]
+fn allowed_token_minimum_cannot_be_negative() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, _) = setup(&env);
+
+    let token = env.register_stellar_asset_contract_v2(admin).address();
+    let info = TokenInfo {
+        token,
+        symbol: SdkString::from_str(&env, "NEG"),
+        decimals: 7,
+        min_entry_fee: -1,
+    };
+    assert!(factory.try_set_allowed_token(&info).is_err());
+}
+
+#[test]
+fn spawn_next_does_not_shift_the_creator_predicted_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+    factory.set_template(&symbol_short!("daily"), &template(&env, &token));
+    let series_id = factory.create_series(&symbol_short!("daily"), &1_000, &admin);
+
+    let predicted = factory.predict_address(&admin, &0);
+    let instance = factory.spawn_next(&series_id);
+    assert_ne!(instance, predicted);
+    assert_eq!(create(&env, &factory, &admin, &token), predicted);
+    assert_eq!(factory.get_competition(&instance).creator, admin);
+}
+
+#[test]
+fn series_templates_cannot_roll_over_the_remainder() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+    let mut roll_over = template(&env, &token);
+    roll_over.remainder_policy = RemainderPolicy::RollOver;
+
+    factory.set_template(&symbol_short!("ladder"), &roll_over);
+    assert!(factory.try_create_series(&symbol_short!("ladder"), &1_000, &admin).is_err());
+
+    // Nem quando o template muda depois da criação da série
+    factory.set_template(&symbol_short!("daily"), &template(&env, &token));
+    let series_id = factory.create_series(&symbol_short!("daily"), &1_000, &admin);
+    factory.set_template(&symbol_short!("daily"), &roll_over);
+    assert!(factory.try_spawn_next(&series_id).is_err());
+}
+
+#[test]
+fn factory_pause_reaches_every_child() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+    let child = create(&env, &factory, &admin, &token);
+    let client = competition::Client::new(&env, &child);
+    let players: std::vec::Vec<Address> = (0..2).map(|_| funded_player(&env, &token)).collect();
+    client.join(&players[0], &symbol_short!("alice"), &Vec::new(&env));
+
+    factory.pause();
+    assert!(client.is_paused());
+    assert!(client.try_join(&players[1], &symbol_short!("bob"), &Vec::new(&env)).is_err());
+    assert!(factory
+        .try_create_competition(&admin, &0, &admin, &config(&env, &token), &symbol_short!("chess"))
+        .is_err());
+
+    // Reembolsos não dependem da pausa
+    client.cancel();
+    assert_eq!(token_balance(&env, &token, &players[0]), ENTRY_FEE);
+
+    factory.unpause();
+    assert!(!client.is_paused());
+}
+
+#[test]
+fn upgrade_children_migrates_the_upgraded_children() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, token) = setup(&env);
+    let child = create(&env, &factory, &admin, &token);
+    let client = competition::Client::new(&env, &child);
+    client.join(&funded_player(&env, &token), &symbol_short!("alice"), &Vec::new(&env));
+
+    // Filha no layout v3, anterior à custódia registrada
+    env.as_contract(&child, || {
+        env.storage().instance().set(&competition::DataKey::SchemaVersion, &3u32);
+        env.storage().instance().remove(&competition::DataKey::Escrow);
+    });
+
+    assert_eq!(factory.upgrade_children(&upgraded_wasm_hash(&env), &0, &10), 1);
+    assert_eq!(client.get_schema_version(), 4);
+    assert_eq!(client.get_escrow_total(), ENTRY_FEE);
+}
+
+#[test]
+fn migrate_moves_v1_competitions_into_the_registry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (factory, admin, _) = setup(&env);
+
+    let legacy = vec![&env, Address::generate(&env), Address::generate(&env)];
+    env.as_contract(&factory.address, || {
+        env.storage().instance().remove(&DataKey::SchemaVersion);
+        env.storage().instance().set(&DataKey::Competitions, &legacy);
+    });
+    assert_eq!(factory.get_schema_version(), 1);
+
+    factory.migrate();
+    assert_eq!(factory.get_schema_version(), SCHEMA_VERSION);
+    assert_eq!(factory.get_competitions(&0, &10), legacy);
+    assert_eq!(factory.get_competition_count_by_status(&CompetitionStatus::Open), 2);
+    let record = factory.get_competition(&legacy.get(0).unwrap());
+    assert_eq!(record.creator, admin);
+    assert_eq!(record.token, Address::from_string(&SdkString::from_str(&env, LEGACY_XLM_TOKEN)));
+    assert!(factory.try_migrate().is_err());
+}
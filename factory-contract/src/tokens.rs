@@ -0,0 +1,58 @@
+use soroban_sdk::{contracttype, Address, Env, String, Vec};
+
+use crate::DataKey;
+
+// Token aceito como moeda das competições, com os metadados exibidos pelo frontend.
+#[contracttype]
+#[derive(Clone)]
+pub struct TokenInfo {
+    pub token: Address,
+    pub symbol: String,
+    pub decimals: u32,
+    pub min_entry_fee: i128,
+}
+
+pub fn get(env: &Env, token: &Address) -> Option<TokenInfo> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::AllowedToken(token.clone()))
+}
+
+pub fn set(env: &Env, info: &TokenInfo) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::AllowedToken(info.token.clone()), info);
+
+    let mut allowed = list(env);
+    if !allowed.contains(&info.token) {
+        allowed.push_back(info.token.clone());
+        env.storage().instance().set(&DataKey::AllowedTokens, &allowed);
+    }
+}
+
+pub fn remove(env: &Env, token: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::AllowedToken(token.clone()));
+
+    let mut allowed = list(env);
+    if let Some(index) = allowed.first_index_of(token) {
+        allowed.remove(index);
+        env.storage().instance().set(&DataKey::AllowedTokens, &allowed);
+    }
+}
+
+pub fn list(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::AllowedTokens)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+// Garante que o token está na allowlist e que a taxa de entrada respeita o mínimo dele.
+pub fn require_allowed(env: &Env, token: &Address, entry_fee: i128) {
+    let info = get(env, token).unwrap_or_else(|| panic!("Token is not allowed"));
+    if entry_fee < info.min_entry_fee {
+        panic!("Entry fee is below the token minimum");
+    }
+}
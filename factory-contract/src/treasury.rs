@@ -0,0 +1,73 @@
+use soroban_sdk::{token, Address, Env};
+
+use crate::DataKey;
+
+// Tesouraria da plataforma: saldo disponível por token, total histórico de taxas por token
+// e total de taxas pagas por cada competição.
+pub fn deposit(env: &Env, competition: &Address, token: &Address, amount: i128) {
+    let storage = env.storage().persistent();
+
+    let balance_key = DataKey::TreasuryBalance(token.clone());
+    let balance: i128 = storage.get(&balance_key).unwrap_or(0);
+    storage.set(&balance_key, &(balance + amount));
+
+    let lifetime_key = DataKey::LifetimeFees(token.clone());
+    let lifetime: i128 = storage.get(&lifetime_key).unwrap_or(0);
+    storage.set(&lifetime_key, &(lifetime + amount));
+
+    let competition_key = DataKey::CompetitionFees(competition.clone());
+    let competition_total: i128 = storage.get(&competition_key).unwrap_or(0);
+    storage.set(&competition_key, &(competition_total + amount));
+}
+
+// Transfere a taxa da competição para a fábrica e devolve quanto de fato chegou.
+pub fn collect(env: &Env, competition: &Address, token: &Address, amount: i128) -> i128 {
+    let token_client = token::Client::new(env, token);
+    let factory = env.current_contract_address();
+
+    let balance_before = token_client.balance(&factory);
+    token_client.transfer(competition, &factory, &amount);
+    let received = token_client.balance(&factory) - balance_before;
+    if received <= 0 {
+        panic!("Fee deposit was not received");
+    }
+    received
+}
+
+pub fn withdraw(env: &Env, token: &Address, to: &Address, amount: i128) {
+    if amount <= 0 {
+        panic!("Withdraw amount must be positive");
+    }
+
+    let balance_key = DataKey::TreasuryBalance(token.clone());
+    let balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+    if amount > balance {
+        panic!("Insufficient treasury balance");
+    }
+    env.storage()
+        .persistent()
+        .set(&balance_key, &(balance - amount));
+
+    token::Client::new(env, token).transfer(&env.current_contract_address(), to, &amount);
+}
+
+pub fn balance(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::TreasuryBalance(token.clone()))
+        .unwrap_or(0)
+}
+
+pub fn lifetime_fees(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::LifetimeFees(token.clone()))
+        .unwrap_or(0)
+}
+
+pub fn competition_fees(env: &Env, competition: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::CompetitionFees(competition.clone()))
+        .unwrap_or(0)
+}